@@ -1,9 +1,14 @@
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
+use chrono::NaiveDate;
 use clap::{Parser, Subcommand};
 
 use crate::client::{TIMEOUT, USER_AGENT};
+use crate::offer_ref::OfferRef;
+use crate::tag_expression::TagExpression;
 
+const LISTEN: &str = "127.0.0.1:8080";
 const DESCRIPTION: &str = "Bump advertisements on wg-gesucht.de";
 
 /// Command line arguments
@@ -23,6 +28,154 @@ pub enum Mode {
         #[clap(index = 1)]
         config_file: PathBuf,
     },
+    #[clap(about = "Interactively create a config file")]
+    Init {
+        #[clap(index = 1)]
+        config_file: PathBuf,
+    },
+    #[clap(about = "Show the operations needed to reach the desired state of the offers")]
+    Plan {
+        #[clap(index = 1)]
+        config_file: PathBuf,
+    },
+    #[clap(about = "Serve a local HTTP API to control the accounts of a config file")]
+    Serve {
+        #[clap(index = 1)]
+        config_file: PathBuf,
+        #[clap(short, long, default_value = LISTEN)]
+        listen: SocketAddr,
+    },
+    #[clap(about = "Edit the details of an offer")]
+    Edit(EditArgs),
+    #[clap(about = "Export all offers into a JSON or TOML backup file")]
+    Export {
+        #[clap(index = 1)]
+        config_file: PathBuf,
+        #[clap(index = 2)]
+        output: PathBuf,
+        #[clap(long, help = "Only export the offers of this account")]
+        account: Option<String>,
+        #[clap(
+            long,
+            value_name = "SELECTOR",
+            help = "Only export these offers (IDs, names, @groups or tag:expressions)"
+        )]
+        offers: Vec<OfferRef>,
+    },
+    #[clap(about = "Restore offers from a backup file", visible_alias = "restore")]
+    Import(ImportArgs),
+    #[clap(about = "Export the applicants of the configured offers to a CSV file")]
+    Applicants {
+        #[clap(index = 1)]
+        config_file: PathBuf,
+        #[clap(index = 2)]
+        output: PathBuf,
+        #[clap(long, help = "Only export the applicants of this account")]
+        account: Option<String>,
+        #[clap(
+            long,
+            value_name = "SELECTOR",
+            help = "Only export these offers (IDs, names, @groups or tag:expressions)"
+        )]
+        offers: Vec<OfferRef>,
+    },
+    #[clap(about = "Write the scheduled viewings to an iCalendar file")]
+    Viewings {
+        #[clap(index = 1)]
+        viewings: PathBuf,
+        #[clap(index = 2)]
+        output: PathBuf,
+    },
+    #[clap(about = "Send the templated first reply to new applicants")]
+    AutoReply {
+        #[clap(index = 1)]
+        config_file: PathBuf,
+        #[clap(long, help = "Only reply to applicants of this account")]
+        account: Option<String>,
+        #[clap(long, help = "Only show the replies")]
+        dry_run: bool,
+    },
+    #[clap(about = "List the conversations with applicants per offer")]
+    Messages {
+        #[clap(index = 1)]
+        config_file: PathBuf,
+        #[clap(long, help = "Only list the conversations of this account")]
+        account: Option<String>,
+        #[clap(long, help = "Only list unread conversations")]
+        unread: bool,
+    },
+    #[clap(about = "Try the scraping strategies against a saved offers page")]
+    Diagnose {
+        #[clap(index = 1)]
+        config_file: PathBuf,
+        #[clap(index = 2)]
+        page: PathBuf,
+    },
+    #[clap(about = "Watch the public search for new listings")]
+    Watch {
+        #[clap(index = 1)]
+        config_file: PathBuf,
+        #[clap(long, default_value_t = 30, help = "Minutes between checks")]
+        interval_min: u64,
+        #[clap(long, help = "Check only once and exit")]
+        once: bool,
+    },
+    #[clap(about = "Show the positions of the offers in the public search")]
+    Rank {
+        #[clap(index = 1)]
+        config_file: PathBuf,
+        #[clap(long, help = "Only rank the offers of this account")]
+        account: Option<String>,
+        #[clap(long, help = "Do not bump offers that rank too low")]
+        dry_run: bool,
+    },
+    #[clap(about = "Show the view and contact counts of the offers")]
+    Stats {
+        #[clap(index = 1)]
+        config_file: PathBuf,
+        #[clap(long, help = "Only show the offers of this account")]
+        account: Option<String>,
+        #[clap(
+            long,
+            help = "Store the counts as a snapshot",
+            conflicts_with = "history"
+        )]
+        record: bool,
+        #[clap(long, help = "Show the recorded snapshots")]
+        history: bool,
+    },
+    #[clap(about = "Mirror the configured photo directories to the offers' galleries")]
+    Photos {
+        #[clap(index = 1)]
+        config_file: PathBuf,
+        #[clap(long, help = "Only sync the photos of this account")]
+        account: Option<String>,
+        #[clap(long, help = "Only show the changes")]
+        dry_run: bool,
+        #[clap(short, long, help = "Apply the changes without asking")]
+        yes: bool,
+        #[clap(
+            long,
+            help = "Also delete images that were not uploaded from the directory"
+        )]
+        prune: bool,
+    },
+    #[clap(about = "Manage the offers of all accounts in an interactive terminal UI")]
+    Tui {
+        #[clap(index = 1)]
+        config_file: PathBuf,
+    },
+    #[clap(about = "Push the templated description texts to the offers")]
+    SyncTexts {
+        #[clap(index = 1)]
+        config_file: PathBuf,
+        #[clap(long, help = "Only sync the texts of this account")]
+        account: Option<String>,
+        #[clap(long, help = "Only show the changes")]
+        dry_run: bool,
+        #[clap(short, long, help = "Apply the changes without asking")]
+        yes: bool,
+    },
 }
 
 #[derive(Debug, Parser)]
@@ -35,6 +188,20 @@ pub struct Parameters {
     pub(crate) user_agent: String,
     #[clap(short, long, name = "SECS", default_value_t = TIMEOUT.as_secs())]
     pub(crate) timeout: u64,
+    #[clap(long, help = "Do not keep the session logged in beyond this run")]
+    pub(crate) no_autologin: bool,
+    #[clap(
+        long,
+        value_name = "CODE",
+        help = "Verification code if the login requires one"
+    )]
+    pub(crate) otp: Option<String>,
+    #[clap(
+        short,
+        long,
+        help = "Config file defining the names, tags and groups of the account's offers"
+    )]
+    pub(crate) config_file: Option<PathBuf>,
     #[clap(subcommand)]
     pub(crate) action: Action,
 }
@@ -43,17 +210,88 @@ pub struct Parameters {
 pub enum Action {
     #[clap(about = "Activate offers")]
     Activate {
-        #[clap(index = 1)]
-        offers: Vec<u32>,
+        #[clap(
+            index = 1,
+            help = "IDs, names, @groups or tag:expressions of the offers"
+        )]
+        offers: Vec<OfferRef>,
+        #[clap(long, help = "Also select the offers matching this tag expression")]
+        tag: Vec<TagExpression>,
+        #[clap(long, help = "Also select the offers of this group")]
+        group: Vec<String>,
     },
     #[clap(about = "Bump offers to newest")]
     Bump {
-        #[clap(index = 1)]
-        offers: Vec<u32>,
+        #[clap(
+            index = 1,
+            help = "IDs, names, @groups or tag:expressions of the offers"
+        )]
+        offers: Vec<OfferRef>,
+        #[clap(long, help = "Also select the offers matching this tag expression")]
+        tag: Vec<TagExpression>,
+        #[clap(long, help = "Also select the offers of this group")]
+        group: Vec<String>,
     },
     #[clap(about = "Deactivate offers")]
     Deactivate {
-        #[clap(index = 1)]
-        offers: Vec<u32>,
+        #[clap(
+            index = 1,
+            help = "IDs, names, @groups or tag:expressions of the offers"
+        )]
+        offers: Vec<OfferRef>,
+        #[clap(long, help = "Also select the offers matching this tag expression")]
+        tag: Vec<TagExpression>,
+        #[clap(long, help = "Also select the offers of this group")]
+        group: Vec<String>,
     },
 }
+
+#[derive(Debug, Parser)]
+pub struct EditArgs {
+    #[clap(index = 1)]
+    pub(crate) config_file: PathBuf,
+    #[clap(index = 2, help = "ID or name of the offer")]
+    pub(crate) offer: OfferRef,
+    #[clap(long, help = "Account that owns the offer")]
+    pub(crate) account: Option<String>,
+    #[clap(long, value_name = "EUR", help = "Rent")]
+    pub(crate) rent: Option<u32>,
+    #[clap(long, value_name = "EUR", help = "Utility costs")]
+    pub(crate) utilities: Option<u32>,
+    #[clap(long, value_name = "EUR", help = "Other costs")]
+    pub(crate) other_costs: Option<u32>,
+    #[clap(long, value_name = "EUR", help = "Bond")]
+    pub(crate) bond: Option<u32>,
+    #[clap(long, value_name = "SQM", help = "Room size")]
+    pub(crate) size: Option<u32>,
+    #[clap(long, value_name = "FROM", help = "Available from (YYYY-MM-DD)")]
+    pub(crate) available_from: Option<NaiveDate>,
+    #[clap(long, value_name = "UNTIL", help = "Available until (YYYY-MM-DD)")]
+    pub(crate) available_until: Option<NaiveDate>,
+    #[clap(long, conflicts_with = "description_file", help = "Description text")]
+    pub(crate) description: Option<String>,
+    #[clap(
+        long,
+        value_name = "FILE",
+        help = "Read the description text from a file"
+    )]
+    pub(crate) description_file: Option<PathBuf>,
+    #[clap(long, help = "Only show the changes")]
+    pub(crate) dry_run: bool,
+    #[clap(short, long, help = "Apply the changes without asking")]
+    pub(crate) yes: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct ImportArgs {
+    #[clap(index = 1)]
+    pub(crate) config_file: PathBuf,
+    #[clap(index = 2)]
+    pub(crate) backup: PathBuf,
+    #[clap(long, help = "Only restore the offers of this account")]
+    pub(crate) account: Option<String>,
+    #[clap(long, help = "Only show the changes")]
+    pub(crate) dry_run: bool,
+    #[clap(short, long, help = "Apply the changes without asking")]
+    pub(crate) yes: bool,
+}
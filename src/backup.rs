@@ -0,0 +1,187 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::diff::value_to_string;
+
+/// Current version of the backup format.
+pub const VERSION: u32 = 1;
+
+/// Fields of the offer details that are managed by wg-gesucht and cannot be restored.
+const READ_ONLY_FIELDS: [&str; 7] = [
+    "offer_id",
+    "user_id",
+    "date_created",
+    "date_edited",
+    "deactivated",
+    "images",
+    "number_of_views",
+];
+
+/// Backup of the offers of multiple accounts.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Backup {
+    pub(crate) version: u32,
+    pub(crate) exported_at: DateTime<Utc>,
+    pub(crate) accounts: Vec<AccountBackup>,
+}
+
+/// Backup of the offers of a single account.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct AccountBackup {
+    pub(crate) user_name: String,
+    pub(crate) offers: Vec<OfferBackup>,
+}
+
+/// Backup of a single offer.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct OfferBackup {
+    pub(crate) id: u32,
+    pub(crate) title: Option<String>,
+    pub(crate) active: bool,
+    pub(crate) details: Map<String, Value>,
+}
+
+impl Backup {
+    /// Create a new backup of the given accounts.
+    #[must_use]
+    pub fn new(accounts: Vec<AccountBackup>) -> Self {
+        Self {
+            version: VERSION,
+            exported_at: Utc::now(),
+            accounts,
+        }
+    }
+}
+
+impl OfferBackup {
+    /// Create a new offer backup.
+    ///
+    /// `null` values are removed from the details, since they cannot be represented in all formats.
+    #[must_use]
+    pub fn new(id: u32, title: Option<String>, active: bool, details: Map<String, Value>) -> Self {
+        Self {
+            id,
+            title,
+            active,
+            details: strip_nulls(details),
+        }
+    }
+
+    /// Return the details that can be restored.
+    #[must_use]
+    pub fn restorable_details(&self) -> Map<String, Value> {
+        self.details
+            .iter()
+            .filter(|(field, _)| !READ_ONLY_FIELDS.contains(&field.as_str()))
+            .map(|(field, value)| (field.clone(), value.clone()))
+            .collect()
+    }
+
+    /// Return the restorable details as strings for comparison.
+    #[must_use]
+    pub fn restorable_fields(&self) -> Vec<(String, String)> {
+        self.restorable_details()
+            .into_iter()
+            .filter_map(|(field, value)| value_to_string(&value).map(|value| (field, value)))
+            .collect()
+    }
+}
+
+fn strip_nulls(map: Map<String, Value>) -> Map<String, Value> {
+    map.into_iter()
+        .filter_map(|(key, value)| strip_null(value).map(|value| (key, value)))
+        .collect()
+}
+
+fn strip_null(value: Value) -> Option<Value> {
+    match value {
+        Value::Null => None,
+        Value::Array(values) => Some(Value::Array(
+            values.into_iter().filter_map(strip_null).collect(),
+        )),
+        Value::Object(map) => Some(Value::Object(strip_nulls(map))),
+        other => Some(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Map, Value, json};
+    use serde_rw::{FromFile, ToFile};
+
+    use super::{AccountBackup, Backup, OfferBackup, strip_nulls};
+
+    fn details(value: Value) -> Map<String, Value> {
+        serde_json::from_value(value).expect("details object")
+    }
+
+    fn backup() -> Backup {
+        Backup::new(vec![AccountBackup {
+            user_name: "user@example.com".to_string(),
+            offers: vec![OfferBackup::new(
+                42,
+                Some("Bright room".to_string()),
+                true,
+                details(json!({
+                    "offer_id": "42",
+                    "rent_costs": "480",
+                    "available_to_date": null,
+                    "flatshare_properties": [1, "2", null, {"a": null, "b": 1.5}],
+                    "images": [{"id": 7}],
+                })),
+            )],
+        }])
+    }
+
+    #[test]
+    fn strip() {
+        assert_eq!(
+            strip_nulls(details(json!({
+                "a": null,
+                "b": [null, 1, [null]],
+                "c": {"d": null, "e": {"f": null}},
+                "g": "",
+            }))),
+            details(json!({"b": [1, []], "c": {"e": {}}, "g": ""}))
+        );
+    }
+
+    #[test]
+    fn restorable() {
+        let backup = backup();
+        let offer = &backup.accounts[0].offers[0];
+        assert_eq!(
+            offer.restorable_details(),
+            details(json!({
+                "rent_costs": "480",
+                "flatshare_properties": [1, "2", {"b": 1.5}],
+            }))
+        );
+        assert_eq!(
+            offer.restorable_fields(),
+            [
+                (
+                    "flatshare_properties".to_string(),
+                    r#"[1,"2",{"b":1.5}]"#.to_string()
+                ),
+                ("rent_costs".to_string(), "480".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        for extension in ["json", "toml"] {
+            let path = std::env::temp_dir().join(format!(
+                "wg_gesucht_updater-backup-{}.{extension}",
+                std::process::id()
+            ));
+            let backup = backup();
+            backup.write_to_file_pretty(&path).expect("written backup");
+            let read = Backup::from_file(&path).expect("read backup");
+            std::fs::remove_file(&path).expect("removed backup");
+            assert_eq!(read, backup, "{extension}");
+        }
+    }
+}
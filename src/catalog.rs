@@ -0,0 +1,276 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::anyhow;
+
+use crate::config_file::NamedOffer;
+use crate::offer_ref::OfferRef;
+use crate::tag_expression::TagExpression;
+
+/// Maximum depth of groups referencing other groups.
+const MAX_GROUP_DEPTH: usize = 16;
+
+/// The named offers and groups of an account used to resolve offer references.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Catalog {
+    offers: Vec<NamedOffer>,
+    groups: BTreeMap<String, Vec<OfferRef>>,
+}
+
+impl Catalog {
+    #[must_use]
+    pub const fn new(offers: Vec<NamedOffer>, groups: BTreeMap<String, Vec<OfferRef>>) -> Self {
+        Self { offers, groups }
+    }
+
+    /// Return the IDs of the referenced offers.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] if a name or group is unknown, a group references itself
+    /// or a tag expression matches no offer.
+    pub fn resolve(&self, offer: &OfferRef) -> anyhow::Result<Vec<u32>> {
+        self.resolve_nested(offer, 0)
+    }
+
+    /// Return the IDs of the referenced offers, each only once and in the given order.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] if any of the references could not be resolved.
+    pub fn select(&self, offers: &[OfferRef]) -> anyhow::Result<Vec<u32>> {
+        let mut ids = Vec::new();
+
+        for offer in offers {
+            ids.extend(self.resolve(offer)?);
+        }
+
+        let mut seen = BTreeSet::new();
+        ids.retain(|&id| seen.insert(id));
+        Ok(ids)
+    }
+
+    /// Return the IDs of the named offers whose tags match the expression.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] if no offer matches.
+    pub fn matching(&self, expression: &TagExpression) -> anyhow::Result<Vec<u32>> {
+        let ids: Vec<u32> = self
+            .offers
+            .iter()
+            .filter(|offer| expression.matches(&offer.tags))
+            .map(|offer| offer.id)
+            .collect();
+
+        if ids.is_empty() {
+            Err(anyhow!("No offer matches the tags {expression}"))
+        } else {
+            Ok(ids)
+        }
+    }
+
+    /// Check that all names can be referenced unambiguously and all groups can be resolved.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] naming the first offer name that is used twice or would be read
+    /// as another kind of reference, or the first group that could not be resolved.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let mut names = BTreeSet::new();
+
+        for offer in &self.offers {
+            if !names.insert(offer.name.as_str()) {
+                return Err(anyhow!(
+                    "Offer name {:?} is used more than once",
+                    offer.name
+                ));
+            }
+
+            if !matches!(offer.name.parse(), Ok(OfferRef::Name(_))) {
+                return Err(anyhow!(
+                    "Offer name {:?} would be read as an ID, group or tag expression",
+                    offer.name
+                ));
+            }
+        }
+
+        for (group, offers) in &self.groups {
+            self.select(offers)
+                .map_err(|error| anyhow!("Group {group:?}: {error}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Return the name of an offer.
+    #[must_use]
+    pub fn name(&self, id: u32) -> Option<&str> {
+        self.offers
+            .iter()
+            .find(|offer| offer.id == id)
+            .map(|offer| offer.name.as_str())
+    }
+
+    /// Return the ID of an offer followed by its name, if any.
+    #[must_use]
+    pub fn label(&self, id: u32) -> String {
+        self.name(id)
+            .map_or_else(|| format!("#{id}"), |name| format!("#{id} ({name})"))
+    }
+
+    fn resolve_nested(&self, offer: &OfferRef, depth: usize) -> anyhow::Result<Vec<u32>> {
+        match offer {
+            OfferRef::Id(id) => Ok(vec![*id]),
+            OfferRef::Name(name) => self
+                .offers
+                .iter()
+                .find(|offer| offer.name == *name)
+                .map(|offer| vec![offer.id])
+                .ok_or_else(|| anyhow!("No offer named {name:?}")),
+            OfferRef::Group(group) => {
+                if depth >= MAX_GROUP_DEPTH {
+                    return Err(anyhow!("Group {group:?} is nested too deeply or recursive"));
+                }
+
+                let offers = self
+                    .groups
+                    .get(group)
+                    .ok_or_else(|| anyhow!("No group named {group:?}"))?;
+                let mut ids = Vec::new();
+
+                for offer in offers {
+                    ids.extend(self.resolve_nested(offer, depth + 1)?);
+                }
+
+                Ok(ids)
+            }
+            OfferRef::Tags(expression) => self.matching(expression),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Catalog;
+    use crate::offer_ref::OfferRef;
+
+    fn catalog(offers: serde_json::Value, groups: serde_json::Value) -> Catalog {
+        Catalog::new(
+            serde_json::from_value(offers).expect("Offers should be valid."),
+            serde_json::from_value(groups).expect("Groups should be valid."),
+        )
+    }
+
+    fn example() -> Catalog {
+        catalog(
+            json!([
+                {"id": 1, "name": "altona", "tags": ["haus-a", "winter"]},
+                {"id": 2, "name": "eimsbuettel", "tags": ["haus-a"]},
+                {"id": 3, "name": "ottensen", "tags": ["haus-b"]},
+            ]),
+            json!({
+                "winter": ["altona", 4, "tag:haus-b"],
+                "all": ["@winter", "tag:haus-a"],
+                "loop": ["@loop"],
+            }),
+        )
+    }
+
+    fn resolve(catalog: &Catalog, offer: &str) -> anyhow::Result<Vec<u32>> {
+        catalog.resolve(&offer.parse::<OfferRef>()?)
+    }
+
+    #[test]
+    fn resolve_references() {
+        let catalog = example();
+        assert_eq!(resolve(&catalog, "7").expect("ID should resolve."), [7]);
+        assert_eq!(
+            resolve(&catalog, "eimsbuettel").expect("Name should resolve."),
+            [2]
+        );
+        assert_eq!(
+            resolve(&catalog, "@winter").expect("Group should resolve."),
+            [1, 4, 3]
+        );
+        assert_eq!(
+            resolve(&catalog, "@all").expect("Nested group should resolve."),
+            [1, 4, 3, 1, 2]
+        );
+        assert_eq!(
+            resolve(&catalog, "tag:haus-a & !winter").expect("Tags should resolve."),
+            [2]
+        );
+    }
+
+    #[test]
+    fn resolve_errors() {
+        let catalog = example();
+
+        for (offer, error) in [
+            ("wandsbek", "No offer named \"wandsbek\""),
+            ("@summer", "No group named \"summer\""),
+            ("@loop", "Group \"loop\" is nested too deeply or recursive"),
+            ("tag:haus-c", "No offer matches the tags haus-c"),
+        ] {
+            assert_eq!(
+                resolve(&catalog, offer)
+                    .expect_err("Reference should not resolve.")
+                    .to_string(),
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn select() {
+        let offers: Vec<OfferRef> = serde_json::from_value(json!(["@all", 3, "ottensen", 5]))
+            .expect("References should be valid.");
+        assert_eq!(
+            example()
+                .select(&offers)
+                .expect("References should resolve."),
+            [1, 4, 3, 2, 5]
+        );
+    }
+
+    #[test]
+    fn validate() {
+        assert!(
+            catalog(json!([{"id": 1, "name": "altona"}]), json!({}))
+                .validate()
+                .is_ok()
+        );
+        assert_eq!(
+            example()
+                .validate()
+                .expect_err("Recursive group should be rejected.")
+                .to_string(),
+            "Group \"loop\": Group \"loop\" is nested too deeply or recursive"
+        );
+
+        for (offers, error) in [
+            (
+                json!([{"id": 1, "name": "altona"}, {"id": 2, "name": "altona"}]),
+                "Offer name \"altona\" is used more than once",
+            ),
+            (
+                json!([{"id": 1, "name": "1234567"}]),
+                "Offer name \"1234567\" would be read as an ID, group or tag expression",
+            ),
+            (
+                json!([{"id": 1, "name": "@altona"}]),
+                "Offer name \"@altona\" would be read as an ID, group or tag expression",
+            ),
+        ] {
+            assert_eq!(
+                catalog(offers, json!({}))
+                    .validate()
+                    .expect_err("Name should be rejected.")
+                    .to_string(),
+                error
+            );
+        }
+    }
+}
@@ -1,48 +1,73 @@
 use std::borrow::Cow;
+use std::sync::Arc;
 use std::time::Duration;
 
-use reqwest::{Request, Response};
+use anyhow::anyhow;
+use log::{debug, info};
+use reqwest::{IntoUrl, Method, Request, RequestBuilder, Response, Url};
 use scraper::Html;
-use session::Session;
+use serde_json::{Map, Value};
+pub use session::Session;
+use transport::Transport;
 
 use crate::auth_data::AuthData;
+use crate::config_file::{Scraping, SearchQuery, default_verification_fields};
+use crate::credentials::Credentials;
 use crate::html_ext::HtmlExt;
+use crate::listing::Listing;
 use crate::login_data::LoginData;
+use crate::metrics;
 use crate::response_ext::ResponseExt;
+use crate::verification_data::VerificationData;
 
+mod fixture;
 mod session;
+pub mod transport;
 
 const LOGIN_URL: &str = "https://www.wg-gesucht.de/ajax/sessions.php?action=login";
 const OFFERS_LIST_URL: &str = "https://www.wg-gesucht.de/meine-anzeigen.html";
+const SEARCH_URL: &str = "https://www.wg-gesucht.de/";
 const CLIENT_ID: &str = "wg_desktop_website";
 pub const TIMEOUT: Duration = Duration::from_secs(10);
 pub const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/116.0.0.0 Safari/537.36";
 
 /// Client to the wg-gesucht web API.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Client {
     timeout: Duration,
     user_agent: Cow<'static, str>,
+    scraping: Scraping,
     #[allow(clippy::struct_field_names)]
     client: reqwest::Client,
+    transport: Arc<dyn Transport>,
 }
 
 impl Client {
     /// Create a new client to the "WG gesucht" API.
     ///
-    /// # Errors
-    ///
-    /// Return an [`anyhow::Error`] if the session client could not be constructed.
+    /// The transport is chosen by the environment, see [`transport::from_env`].
+    #[must_use]
+    pub fn new(timeout: Duration, user_agent: Cow<'static, str>, scraping: Scraping) -> Self {
+        Self::with_transport(timeout, user_agent, scraping, transport::from_env())
+    }
+
+    /// Create a new client to the "WG gesucht" API that executes its requests via `transport`.
     #[allow(clippy::missing_panics_doc)]
     #[must_use]
-    pub fn new(timeout: Duration, user_agent: Cow<'static, str>) -> Self {
+    pub fn with_transport(
+        timeout: Duration,
+        user_agent: Cow<'static, str>,
+        scraping: Scraping,
+        transport: Arc<dyn Transport>,
+    ) -> Self {
         Self {
             client: reqwest::Client::builder()
-                .cookie_store(true)
                 .build()
                 .expect("Client builder should never fail."),
+            transport,
             timeout,
             user_agent,
+            scraping,
         }
     }
 
@@ -50,19 +75,74 @@ impl Client {
     ///
     /// A login must be performed as the first call to the API
     /// in order to use subsequent requests to modify offers.
+    /// The session keeps the credentials to log in again once its access token expires.
     ///
     /// # Errors
     ///
     /// Return an [`anyhow::Error`] on request errors
-    pub async fn login(self, user_name: &str, password: &str) -> anyhow::Result<Session> {
-        self.get_auth_data(user_name, password)
-            .await
-            .map(|auth_data| Session::new(self.client, auth_data, self.timeout, self.user_agent))
+    pub async fn login(self, credentials: Credentials) -> anyhow::Result<Session> {
+        let auth_data = self.get_auth_data(&credentials, false).await?;
+        Ok(Session::new(self, credentials, auth_data))
+    }
+
+    /// Return a page of the public search results for the given query.
+    ///
+    /// Pages are counted from zero. This does not require a login.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] on request or parsing errors.
+    pub async fn search(&self, query: &SearchQuery, page: u32) -> anyhow::Result<Vec<Listing>> {
+        Ok(Html::parse_document(&String::from_utf8(
+            metrics::observe(
+                "search",
+                self.transport
+                    .execute(self.build_search_request(query, page)?),
+            )
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?
+            .to_vec(),
+        )?)
+        .scrape_listings())
+    }
+
+    /// Return the listings of the first pages of the public search results for the given query.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] on request or parsing errors.
+    pub async fn search_pages(
+        &self,
+        query: &SearchQuery,
+        pages: u32,
+    ) -> anyhow::Result<Vec<Listing>> {
+        let mut listings = Vec::new();
+
+        for page in 0..pages {
+            let page = self.search(query, page).await?;
+
+            if page.is_empty() {
+                break;
+            }
+
+            listings.extend(page);
+        }
+
+        Ok(listings)
     }
 
-    async fn get_auth_data(&self, user_name: &str, password: &str) -> anyhow::Result<AuthData> {
+    /// Log in and collect the authentication data.
+    ///
+    /// When logging in again (`relogin`), a verification code can only be generated from a TOTP secret.
+    async fn get_auth_data(
+        &self,
+        credentials: &Credentials,
+        relogin: bool,
+    ) -> anyhow::Result<AuthData> {
         let (dev_ref, access_token) = self
-            .get_dev_ref_and_access_token(user_name, password)
+            .get_dev_ref_and_access_token(credentials, relogin)
             .await?;
         let (csrf_token, user_id) = self.get_csrf_token_and_user_id().await?;
         Ok(AuthData::new(
@@ -76,59 +156,305 @@ impl Client {
 
     async fn get_dev_ref_and_access_token(
         &self,
-        user_name: &str,
-        password: &str,
+        credentials: &Credentials,
+        relogin: bool,
     ) -> anyhow::Result<(String, String)> {
-        self.execute_login_request(user_name, password)
+        let response = self.execute_login_request(credentials).await?;
+        let error = match response.scrape_dev_ref_and_access_token() {
+            Ok(tokens) => return Ok(tokens),
+            Err(error) => error,
+        };
+
+        let fields = self
+            .scraping
+            .verification
+            .as_ref()
+            .map_or_else(default_verification_fields, |endpoint| {
+                endpoint.fields.clone()
+            });
+
+        if !requires_verification(&response.json().await.unwrap_or_default(), &fields) {
+            return Err(error);
+        }
+
+        info!("Login of {} requires verification", credentials.user_name);
+        let Some(endpoint) = &self.scraping.verification else {
+            return Err(anyhow!(
+                "Login of {} requires a verification code, which is not supported \
+                 unless the verification endpoint is configured in [scraping.verification]",
+                credentials.user_name
+            ));
+        };
+        let code = if relogin {
+            credentials
+                .verification
+                .relogin_code(&credentials.user_name)?
+        } else {
+            credentials.verification.code(&credentials.user_name)?
+        };
+        self.execute_verification_request(&endpoint.url, &code, credentials.autologin)
             .await?
             .scrape_dev_ref_and_access_token()
     }
 
     async fn get_csrf_token_and_user_id(&self) -> anyhow::Result<(String, String)> {
-        Html::parse_document(&String::from_utf8(
-            self.client
-                .execute(self.build_offer_list_request()?)
-                .await?
-                .error_for_status()?
-                .bytes()
-                .await?
-                .to_vec(),
-        )?)
-        .scrape_csrf_token_and_user_id()
-        .map(|(csrf_token, user_id)| (csrf_token.to_string(), user_id.to_string()))
+        let page = String::from_utf8(
+            metrics::observe(
+                "offers",
+                self.transport.execute(self.build_offer_list_request()?),
+            )
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?
+            .to_vec(),
+        )?;
+        Html::parse_document(&page)
+            .scrape_csrf_token_and_user_id(&self.scraping)
+            .map_err(|error| match self.scraping.save_page("offers", &page) {
+                Ok(Some(path)) => anyhow!("{error} (page saved to {})", path.display()),
+                Ok(None) => error,
+                Err(save_error) => anyhow!("{error} (could not save page: {save_error})"),
+            })
     }
 
     fn build_offer_list_request(&self) -> reqwest::Result<Request> {
-        self.client
-            .get(OFFERS_LIST_URL)
-            .header("User-Agent", self.user_agent.as_ref())
-            .timeout(self.timeout)
-            .build()
+        self.request(Method::GET, OFFERS_LIST_URL).build()
+    }
+
+    fn build_search_request(&self, query: &SearchQuery, page: u32) -> anyhow::Result<Request> {
+        Ok(self
+            .request(Method::GET, build_search_url(query, page)?)
+            .build()?)
     }
 
-    async fn execute_login_request(
+    async fn execute_login_request(&self, credentials: &Credentials) -> anyhow::Result<Response> {
+        Ok(metrics::observe(
+            "login",
+            self.transport
+                .execute(self.build_login_request(credentials)?),
+        )
+        .await?
+        .error_for_status()?)
+    }
+
+    async fn execute_verification_request(
         &self,
-        user_name: &str,
-        password: &str,
-    ) -> reqwest::Result<Response> {
-        self.client
-            .execute(self.build_login_request(user_name, password)?)
-            .await?
-            .error_for_status()
+        url: &str,
+        code: &str,
+        autologin: bool,
+    ) -> anyhow::Result<Response> {
+        Ok(metrics::observe(
+            "login",
+            self.transport
+                .execute(self.build_verification_request(url, code, autologin)?),
+        )
+        .await?
+        .error_for_status()?)
     }
 
-    fn build_login_request(&self, user_name: &str, password: &str) -> reqwest::Result<Request> {
+    fn build_verification_request(
+        &self,
+        url: &str,
+        code: &str,
+        autologin: bool,
+    ) -> reqwest::Result<Request> {
+        self.request(Method::POST, url)
+            .json(&VerificationData::new(code, autologin))
+            .build()
+    }
+
+    fn build_login_request(&self, credentials: &Credentials) -> reqwest::Result<Request> {
+        self.request(Method::POST, LOGIN_URL)
+            .json(&LoginData::new(
+                &credentials.user_name,
+                &credentials.password,
+                credentials.autologin,
+                "de",
+            ))
+            .build()
+    }
+
+    /// Start building a request with the client's user agent and timeout.
+    fn request(&self, method: Method, url: impl IntoUrl) -> RequestBuilder {
         self.client
-            .post(LOGIN_URL)
-            .json(&LoginData::new(user_name, password, true, "de"))
+            .request(method, url)
             .header("User-Agent", self.user_agent.as_ref())
             .timeout(self.timeout)
-            .build()
     }
 }
 
+/// Determine whether the response to a login request asks for a verification code,
+/// i.e. whether any of the given fields is truthy.
+fn requires_verification(response: &Map<String, Value>, fields: &[String]) -> bool {
+    fields
+        .iter()
+        .any(|field| response.get(field).is_some_and(is_truthy))
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(value) => *value,
+        Value::Number(number) => number.as_u64() != Some(0),
+        Value::String(string) => !string.is_empty() && string != "0",
+        _ => false,
+    }
+}
+
+fn build_search_url(query: &SearchQuery, page: u32) -> anyhow::Result<Url> {
+    let mut url = Url::parse(SEARCH_URL)?.join(&format!(
+        "angebote.{}.{}.1.{page}.html",
+        query.city_id,
+        query.category.id()
+    ))?;
+
+    {
+        let mut pairs = url.query_pairs_mut();
+
+        if let Some(rent_min) = query.rent_min {
+            pairs.append_pair("rMin", &rent_min.to_string());
+        }
+
+        if let Some(rent_max) = query.rent_max {
+            pairs.append_pair("rMax", &rent_max.to_string());
+        }
+    }
+
+    if url.query() == Some("") {
+        url.set_query(None);
+    }
+
+    debug!("Search URL: {url}");
+    Ok(url)
+}
+
 impl Default for Client {
     fn default() -> Self {
-        Self::new(TIMEOUT, Cow::Borrowed(USER_AGENT))
+        Self::new(TIMEOUT, Cow::Borrowed(USER_AGENT), Scraping::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Map, Value, json};
+
+    use std::sync::Arc;
+
+    use super::{build_search_url, is_truthy, requires_verification};
+    use crate::client::transport::stub::{self, Stub};
+    use crate::config_file::Scraping;
+    use crate::config_file::{SearchQuery, default_verification_fields};
+    use crate::credentials::Verification;
+
+    fn response(value: Value) -> Map<String, Value> {
+        serde_json::from_value(value).expect("Response should be an object.")
+    }
+
+    #[test]
+    fn truthy() {
+        for value in [json!(true), json!(1), json!(2), json!("1"), json!("yes")] {
+            assert!(is_truthy(&value), "{value} should be truthy");
+        }
+
+        for value in [
+            json!(false),
+            json!(0),
+            json!(""),
+            json!("0"),
+            json!(null),
+            json!([1]),
+        ] {
+            assert!(!is_truthy(&value), "{value} should not be truthy");
+        }
+    }
+
+    #[test]
+    fn verification() {
+        let fields = default_verification_fields();
+        assert!(requires_verification(
+            &response(json!({"verification_required": true})),
+            &fields
+        ));
+        assert!(requires_verification(
+            &response(json!({"two_factor_required": "1"})),
+            &fields
+        ));
+        assert!(!requires_verification(
+            &response(json!({"verification_required": 0, "two_factor_required": false})),
+            &fields
+        ));
+        assert!(!requires_verification(
+            &response(json!({"error": "invalid"})),
+            &fields
+        ));
+        assert!(requires_verification(
+            &response(json!({"otp": 1})),
+            &["otp".to_string()]
+        ));
+        assert!(!requires_verification(
+            &response(json!({"verification_required": true})),
+            &["otp".to_string()]
+        ));
+    }
+
+    #[test]
+    fn search_url() {
+        for (query, page, expected) in [
+            (
+                json!({"city_id": 55}),
+                0,
+                "https://www.wg-gesucht.de/angebote.55.0.1.0.html",
+            ),
+            (
+                json!({"city_id": 55, "category": "flat", "rent_min": 300, "rent_max": 700}),
+                2,
+                "https://www.wg-gesucht.de/angebote.55.2.1.2.html?rMin=300&rMax=700",
+            ),
+            (
+                json!({"city_id": 55, "category": "house", "rent_max": 900}),
+                1,
+                "https://www.wg-gesucht.de/angebote.55.3.1.1.html?rMax=900",
+            ),
+        ] {
+            let query: SearchQuery = serde_json::from_value(query).expect("valid search query");
+            assert_eq!(
+                build_search_url(&query, page).expect("valid URL").as_str(),
+                expected
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn verification_not_supported() {
+        let stub = Arc::new(Stub::new([stub::json(
+            &json!({"verification_required": true}),
+        )]));
+        let error = stub::client(stub.clone(), Scraping::default())
+            .login(stub::credentials(Verification::Code("123456".to_string())))
+            .await
+            .expect_err("Verification should not be supported.");
+        assert!(error.to_string().contains("not supported"), "{error}");
+        assert_eq!(stub.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn verification_with_configured_endpoint() {
+        let stub = Arc::new(Stub::new([
+            stub::json(&json!({"verification_required": true})),
+            stub::login(&["X-Dev-Ref-No=devref456", "X-Access-Token=token456"]),
+            stub::offers_page("csrf5678"),
+        ]));
+        let session = stub::client(stub.clone(), stub::verification_scraping())
+            .login(stub::credentials(Verification::Code("123456".to_string())))
+            .await
+            .expect("Login should be verified.");
+        assert_eq!(session.user_id(), "1234567");
+        let requests = stub.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[1].url, stub::VERIFICATION_URL);
+        assert_eq!(
+            requests[1].body.as_deref(),
+            Some(r#"{"verification_code":"123456","login_form_autologin":"1"}"#)
+        );
     }
 }
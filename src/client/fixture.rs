@@ -0,0 +1,352 @@
+use std::collections::BTreeSet;
+
+use reqwest::header::HeaderMap;
+use reqwest::{Request, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::diff::value_to_string;
+
+/// Placeholder for redacted secrets.
+const REDACTED: &str = "REDACTED";
+/// Secrets shorter than this are not redacted to not mangle unrelated text.
+const MIN_SECRET_LENGTH: usize = 4;
+/// Headers whose values contain secrets.
+const SENSITIVE_HEADERS: [&str; 5] = [
+    "authorization",
+    "cookie",
+    "set-cookie",
+    "x-authorization",
+    "x-dev-ref-no",
+];
+/// JSON fields whose values are secrets.
+const SENSITIVE_FIELDS: [&str; 7] = [
+    "login_email_username",
+    "login_password",
+    "access_token",
+    "refresh_token",
+    "dev_ref_no",
+    "csrf_token",
+    "password",
+];
+
+/// A recorded request and its response.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Exchange {
+    pub(crate) request: RecordedRequest,
+    pub(crate) response: RecordedResponse,
+}
+
+/// A recorded HTTP request.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct RecordedRequest {
+    pub(crate) method: String,
+    pub(crate) url: String,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: Option<String>,
+}
+
+/// A recorded HTTP response.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct RecordedResponse {
+    pub(crate) status: u16,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: String,
+}
+
+impl From<&Request> for RecordedRequest {
+    fn from(request: &Request) -> Self {
+        let mut headers = headers(request.headers());
+        headers.sort();
+
+        // Multipart boundaries are random and would prevent replaying.
+        for (name, value) in &mut headers {
+            if name == "content-type" && value.starts_with("multipart/form-data") {
+                *value = "multipart/form-data".to_string();
+            }
+        }
+
+        Self {
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+            headers,
+            body: request
+                .body()
+                .and_then(reqwest::Body::as_bytes)
+                .map(|body| String::from_utf8_lossy(body).into_owned()),
+        }
+    }
+}
+
+impl RecordedResponse {
+    /// Record a response from its parts.
+    #[must_use]
+    pub fn new(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Self {
+        Self {
+            status: status.as_u16(),
+            headers: self::headers(headers),
+            body: String::from_utf8_lossy(body).into_owned(),
+        }
+    }
+}
+
+/// Collects secrets from exchanges and replaces them in recorded data.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Redactor {
+    secrets: BTreeSet<String>,
+}
+
+impl Redactor {
+    /// Create a redactor without any known secrets.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            secrets: BTreeSet::new(),
+        }
+    }
+
+    /// Collect the secrets of a request.
+    pub fn learn_request(&mut self, request: &RecordedRequest) {
+        self.learn_headers(&request.headers);
+
+        if let Some(body) = &request.body {
+            self.learn_body(body);
+        }
+    }
+
+    /// Collect the secrets of a response.
+    pub fn learn_response(&mut self, response: &RecordedResponse) {
+        self.learn_headers(&response.headers);
+        self.learn_body(&response.body);
+    }
+
+    /// Return the exchange with all known secrets replaced.
+    #[must_use]
+    pub fn redact(&self, exchange: &Exchange) -> Exchange {
+        Exchange {
+            request: self.redact_request(&exchange.request),
+            response: RecordedResponse {
+                status: exchange.response.status,
+                headers: self.redact_headers(&exchange.response.headers),
+                body: self.redact_text(&exchange.response.body),
+            },
+        }
+    }
+
+    /// Return the request with all known secrets replaced.
+    #[must_use]
+    pub fn redact_request(&self, request: &RecordedRequest) -> RecordedRequest {
+        RecordedRequest {
+            method: request.method.clone(),
+            url: self.redact_text(&request.url),
+            headers: self.redact_headers(&request.headers),
+            body: request.body.as_deref().map(|body| self.redact_text(body)),
+        }
+    }
+
+    fn learn_headers(&mut self, headers: &[(String, String)]) {
+        for (name, value) in headers {
+            match name.as_str() {
+                "cookie" => {
+                    for cookie in value.split(';') {
+                        self.learn_cookie(cookie);
+                    }
+                }
+                "set-cookie" => {
+                    if let Some(cookie) = value.split(';').next() {
+                        self.learn_cookie(cookie);
+                    }
+                }
+                name if SENSITIVE_HEADERS.contains(&name) => {
+                    self.learn(value.strip_prefix("Bearer ").unwrap_or(value));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn learn_cookie(&mut self, cookie: &str) {
+        if let Some((_, value)) = cookie.split_once('=') {
+            self.learn(value.trim());
+        }
+    }
+
+    fn learn_body(&mut self, body: &str) {
+        if let Ok(value) = serde_json::from_str::<Value>(body) {
+            self.learn_json(&value);
+        }
+    }
+
+    fn learn_json(&mut self, value: &Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    if SENSITIVE_FIELDS.contains(&key.as_str())
+                        && let Some(secret) = value_to_string(value)
+                    {
+                        self.learn(&secret);
+                    } else {
+                        self.learn_json(value);
+                    }
+                }
+            }
+            Value::Array(values) => {
+                for value in values {
+                    self.learn_json(value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn learn(&mut self, secret: &str) {
+        if secret.len() >= MIN_SECRET_LENGTH && secret != REDACTED {
+            self.secrets.insert(secret.to_string());
+        }
+    }
+
+    fn redact_headers(&self, headers: &[(String, String)]) -> Vec<(String, String)> {
+        headers
+            .iter()
+            .map(|(name, value)| (name.clone(), self.redact_text(value)))
+            .collect()
+    }
+
+    fn redact_text(&self, text: &str) -> String {
+        let mut secrets: Vec<&String> = self.secrets.iter().collect();
+        secrets.sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+        secrets.into_iter().fold(text.to_string(), |text, secret| {
+            text.replace(secret, REDACTED)
+        })
+    }
+}
+
+fn headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.as_str().to_string(),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Method;
+    use reqwest::multipart::Form;
+
+    use super::{RecordedRequest, RecordedResponse, Redactor};
+
+    fn headers(headers: &[(&str, &str)]) -> Vec<(String, String)> {
+        headers
+            .iter()
+            .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+            .collect()
+    }
+
+    fn request(headers: &[(&str, &str)], body: Option<&str>) -> RecordedRequest {
+        RecordedRequest {
+            method: "POST".to_string(),
+            url: "https://www.wg-gesucht.de/".to_string(),
+            headers: self::headers(headers),
+            body: body.map(ToString::to_string),
+        }
+    }
+
+    #[test]
+    fn redact_headers() {
+        let mut redactor = Redactor::new();
+        let request = request(
+            &[
+                ("cookie", "session=cookie123; lang=de"),
+                ("x-authorization", "Bearer token123"),
+                ("x-dev-ref-no", "devref123"),
+                ("user-agent", "Mozilla/5.0"),
+            ],
+            None,
+        );
+        redactor.learn_request(&request);
+        assert_eq!(
+            redactor.redact_request(&request).headers,
+            headers(&[
+                ("cookie", "session=REDACTED; lang=de"),
+                ("x-authorization", "Bearer REDACTED"),
+                ("x-dev-ref-no", "REDACTED"),
+                ("user-agent", "Mozilla/5.0"),
+            ])
+        );
+    }
+
+    #[test]
+    fn redact_set_cookie() {
+        let mut redactor = Redactor::new();
+        redactor.learn_response(&RecordedResponse {
+            status: 200,
+            headers: headers(&[("set-cookie", "X-Access-Token=token123; path=/; secure")]),
+            body: String::new(),
+        });
+        assert_eq!(
+            redactor.redact_text("Bearer token123; path=/; secure"),
+            "Bearer REDACTED; path=/; secure"
+        );
+    }
+
+    #[test]
+    fn redact_json_fields() {
+        let mut redactor = Redactor::new();
+        let request = request(
+            &[],
+            Some(r#"{"data":[{"csrf_token":"csrf1234","title":"Zimmer in Altona"}]}"#),
+        );
+        redactor.learn_request(&request);
+        assert_eq!(
+            redactor.redact_request(&request).body.as_deref(),
+            Some(r#"{"data":[{"csrf_token":"REDACTED","title":"Zimmer in Altona"}]}"#)
+        );
+    }
+
+    #[test]
+    fn redact_longest_first() {
+        let mut redactor = Redactor::new();
+        redactor.learn("abcd");
+        redactor.learn("abcdef");
+        assert_eq!(redactor.redact_text("abcdef abcd"), "REDACTED REDACTED");
+    }
+
+    #[test]
+    fn min_secret_length() {
+        let mut redactor = Redactor::new();
+        redactor.learn_request(&request(&[("cookie", "a=abc; b=REDACTED")], None));
+        assert!(redactor.secrets.is_empty());
+    }
+
+    #[test]
+    fn multipart_boundary() {
+        let request = reqwest::Client::new()
+            .request(Method::POST, "https://www.wg-gesucht.de/")
+            .multipart(Form::new().text("name", "value"))
+            .build()
+            .expect("Request should be valid.");
+        let recorded = RecordedRequest::from(&request);
+        assert!(
+            recorded.headers.contains(&(
+                "content-type".to_string(),
+                "multipart/form-data".to_string()
+            )),
+            "{:?}",
+            recorded.headers
+        );
+        assert!(
+            recorded
+                .headers
+                .iter()
+                .all(|(_, value)| !value.contains("boundary")),
+            "{:?}",
+            recorded.headers
+        );
+        assert_eq!(recorded.body, None);
+    }
+}
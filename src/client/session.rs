@@ -1,37 +1,52 @@
-use std::borrow::Cow;
-use std::time::Duration;
+use std::sync::{Arc, PoisonError, RwLock};
 
-use log::debug;
-use reqwest::{Client, Request, Response, Url};
+use anyhow::anyhow;
+use log::{debug, info, warn};
+use reqwest::multipart::{Form, Part};
+use reqwest::{Method, Request, Response, StatusCode, Url};
+use scraper::Html;
+use serde::Serialize;
+use serde_json::{Map, Value};
 
+use super::OFFERS_LIST_URL;
 use crate::auth_data::AuthData;
+use crate::conversation::Conversation;
+use crate::credentials::Credentials;
+use crate::csrf_data::CsrfData;
+use crate::diff::value_to_string;
+use crate::edit_data::EditData;
+use crate::html_ext::HtmlExt;
+use crate::image::{Image, LocalImage};
+use crate::image_order_data::ImageOrderData;
+use crate::message_data::MessageData;
+use crate::metrics;
+use crate::offer::Offer;
+use crate::offer_stats::OfferStats;
 use crate::patch_data::PatchData;
 
 const OFFER_MODIFY_URL: &str = "https://www.wg-gesucht.de/api/offers";
+const SESSIONS_URL: &str = "https://www.wg-gesucht.de/api/sessions/users";
+const CONVERSATIONS_URL: &str = "https://www.wg-gesucht.de/api/conversations";
+const CONVERSATIONS_PAGE_SIZE: usize = 100;
 
 /// Session with the wg-gesucht web API
+///
+/// If the server rejects the access token, the session logs in again and retries the request once.
 #[derive(Debug)]
 pub struct Session {
-    client: Client,
-    auth_data: AuthData,
-    timeout: Duration,
-    user_agent: Cow<'static, str>,
+    client: super::Client,
+    credentials: Credentials,
+    auth_data: RwLock<Arc<AuthData>>,
 }
 
 impl Session {
     /// Create a new session to the "WG gesucht" API.
     #[must_use]
-    pub const fn new(
-        client: Client,
-        auth_data: AuthData,
-        timeout: Duration,
-        user_agent: Cow<'static, str>,
-    ) -> Self {
+    pub fn new(client: super::Client, credentials: Credentials, auth_data: AuthData) -> Self {
         Self {
             client,
-            auth_data,
-            timeout,
-            user_agent,
+            credentials,
+            auth_data: RwLock::new(Arc::new(auth_data)),
         }
     }
 
@@ -53,34 +68,434 @@ impl Session {
     ///
     /// Return an [`anyhow::Error`] on request errors.
     pub async fn deactivate(&self, id: u32) -> anyhow::Result<Response> {
+        self.patch(id, true).await
+    }
+
+    /// Activate an offer
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] on request errors.
+    pub async fn activate(&self, id: u32) -> anyhow::Result<Response> {
+        self.patch(id, false).await
+    }
+
+    /// List the offers of the logged-in user.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] on request or parsing errors.
+    pub async fn offers(&self) -> anyhow::Result<Vec<Offer>> {
+        Html::parse_document(&self.offers_page().await?).scrape_offers()
+    }
+
+    /// Return the view and contact counts of the offers of the logged-in user.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] on request or parsing errors.
+    pub async fn offer_stats(&self) -> anyhow::Result<Vec<OfferStats>> {
+        Html::parse_document(&self.offers_page().await?).scrape_offer_stats()
+    }
+
+    /// Fetch the details of an offer.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] on request or parsing errors.
+    pub async fn offer_details(&self, id: u32) -> anyhow::Result<Map<String, Value>> {
         Ok(self
-            .client
-            .execute(self.build_patch_request(id, true)?)
+            .send("details", || self.build_details_request(id))
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    /// Update the details of an offer.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] on request errors.
+    pub async fn edit<T>(&self, id: u32, update: &T) -> anyhow::Result<Response>
+    where
+        T: Serialize + Sync,
+    {
+        let result = self
+            .send("patch", || self.build_edit_request(id, update))
+            .await;
+        metrics::record_patch("edit", &result);
+        Ok(result?.error_for_status()?)
+    }
+
+    /// Create a new offer with the given details.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] on request errors or if the response lacks the new offer's ID.
+    pub async fn create<T>(&self, details: &T) -> anyhow::Result<u32>
+    where
+        T: Serialize + Sync,
+    {
+        let response: Map<String, Value> = self
+            .send("create", || self.build_create_request(details))
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        response
+            .get("offer_id")
+            .and_then(value_to_string)
+            .ok_or_else(|| anyhow!("Response lacks the ID of the created offer"))?
+            .parse()
+            .map_err(Into::into)
+    }
+
+    /// List all conversations in the messaging inbox.
+    ///
+    /// Malformed conversations are skipped with a warning.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] on request errors or if a response lacks the conversations.
+    pub async fn list_conversations(&self) -> anyhow::Result<Vec<Conversation>> {
+        let mut conversations = Vec::new();
+
+        for page in 1.. {
+            let mut response: Map<String, Value> = self
+                .send("conversations", || self.build_conversations_request(page))
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            let Some(Value::Array(entries)) = response
+                .remove("_embedded")
+                .ok_or_else(|| anyhow!("Response lacks the embedded conversations"))?
+                .get_mut("conversations")
+                .map(Value::take)
+            else {
+                return Err(anyhow!("Conversations are not a list"));
+            };
+            let count = entries.len();
+
+            for entry in entries {
+                match entry {
+                    Value::Object(map) => match Conversation::try_from(map) {
+                        Ok(conversation) => conversations.push(conversation),
+                        Err(error) => warn!("Skipping conversation: {error}"),
+                    },
+                    other => warn!("Skipping conversation that is not an object: {other}"),
+                }
+            }
+
+            if count < CONVERSATIONS_PAGE_SIZE {
+                break;
+            }
+        }
+
+        Ok(conversations)
+    }
+
+    /// Send a message in a conversation.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] on request errors.
+    pub async fn send_message(&self, conversation: u32, content: &str) -> anyhow::Result<Response> {
+        Ok(self
+            .send("conversations", || {
+                self.build_message_request(conversation, content)
+            })
             .await?
             .error_for_status()?)
     }
 
-    /// Activate an offer
+    /// Log out, invalidating the session on the server.
     ///
     /// # Errors
     ///
     /// Return an [`anyhow::Error`] on request errors.
-    pub async fn activate(&self, id: u32) -> anyhow::Result<Response> {
+    pub async fn logout(self) -> anyhow::Result<Response> {
         Ok(self
-            .client
-            .execute(self.build_patch_request(id, false)?)
+            .send("logout", || self.build_logout_request())
+            .await?
+            .error_for_status()?)
+    }
+
+    /// Return the ID of the logged-in user.
+    #[must_use]
+    pub fn user_id(&self) -> String {
+        self.auth_data().user_id().to_string()
+    }
+
+    /// List the images attached to an offer.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] on request or parsing errors.
+    pub async fn images(&self, id: u32) -> anyhow::Result<Vec<Image>> {
+        self.send("images", || self.build_images_request(id))
+            .await?
+            .error_for_status()?
+            .json::<Vec<Map<String, Value>>>()
+            .await?
+            .into_iter()
+            .map(Image::try_from)
+            .collect()
+    }
+
+    /// Upload an image and attach it to an offer.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] on request or parsing errors.
+    pub async fn upload_image(&self, id: u32, image: &LocalImage) -> anyhow::Result<Image> {
+        self.send("images", || self.build_upload_request(id, image))
+            .await?
+            .error_for_status()?
+            .json::<Map<String, Value>>()
+            .await?
+            .try_into()
+    }
+
+    /// Delete an image of an offer.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] on request errors.
+    pub async fn delete_image(&self, id: u32, image_id: &str) -> anyhow::Result<Response> {
+        Ok(self
+            .send("images", || self.build_delete_image_request(id, image_id))
+            .await?
+            .error_for_status()?)
+    }
+
+    /// Set the order of the images of an offer.
+    ///
+    /// The first image becomes the title image.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] on request errors.
+    pub async fn arrange_images(&self, id: u32, image_ids: &[String]) -> anyhow::Result<Response> {
+        Ok(self
+            .send("images", || {
+                self.build_arrange_images_request(id, image_ids)
+            })
             .await?
             .error_for_status()?)
     }
 
+    /// Execute a request, logging in again and retrying it once if the access token has expired.
+    async fn send<E>(
+        &self,
+        endpoint: &'static str,
+        build: impl Fn() -> Result<Request, E> + Send,
+    ) -> anyhow::Result<Response>
+    where
+        anyhow::Error: From<E>,
+    {
+        let response = metrics::observe(endpoint, self.client.transport.execute(build()?)).await?;
+
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        info!(
+            "Access token of {} expired, logging in again",
+            self.credentials.user_name
+        );
+        self.refresh().await?;
+        metrics::observe(endpoint, self.client.transport.execute(build()?)).await
+    }
+
+    /// Log in again and replace the authentication data.
+    async fn refresh(&self) -> anyhow::Result<()> {
+        let result = self.client.get_auth_data(&self.credentials, true).await;
+        metrics::record_login(&self.credentials.user_name, result.is_ok());
+        *self
+            .auth_data
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Arc::new(result?);
+        Ok(())
+    }
+
+    fn auth_data(&self) -> Arc<AuthData> {
+        self.auth_data
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    async fn offers_page(&self) -> anyhow::Result<String> {
+        Ok(String::from_utf8(
+            self.send("offers", || self.build_offer_list_request())
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await?
+                .to_vec(),
+        )?)
+    }
+
+    async fn patch(&self, id: u32, deactivated: bool) -> anyhow::Result<Response> {
+        let result = self
+            .send("patch", || self.build_patch_request(id, deactivated))
+            .await;
+        metrics::record_patch(
+            if deactivated {
+                "deactivate"
+            } else {
+                "activate"
+            },
+            &result,
+        );
+        Ok(result?.error_for_status()?)
+    }
+
+    fn build_offer_list_request(&self) -> reqwest::Result<Request> {
+        self.client.request(Method::GET, OFFERS_LIST_URL).build()
+    }
+
+    fn build_logout_request(&self) -> anyhow::Result<Request> {
+        let auth_data = self.auth_data();
+        Ok(self
+            .client
+            .request(Method::DELETE, build_sessions_url(auth_data.user_id()))
+            .headers((&*auth_data).try_into()?)
+            .json(&CsrfData::new(auth_data.csrf_token()))
+            .build()?)
+    }
+
     fn build_patch_request(&self, id: u32, deactivated: bool) -> anyhow::Result<Request> {
+        let auth_data = self.auth_data();
+        Ok(self
+            .client
+            .request(Method::PATCH, build_patch_url(id, auth_data.user_id()))
+            .headers((&*auth_data).try_into()?)
+            .json(&PatchData::new(deactivated, auth_data.csrf_token()))
+            .build()?)
+    }
+
+    fn build_create_request<T>(&self, details: &T) -> anyhow::Result<Request>
+    where
+        T: Serialize,
+    {
+        let auth_data = self.auth_data();
         Ok(self
             .client
-            .patch(build_patch_url(id, self.auth_data.user_id()))
-            .headers((&self.auth_data).try_into()?)
-            .header("User-Agent", self.user_agent.as_ref())
-            .json(&PatchData::new(deactivated, self.auth_data.csrf_token()))
-            .timeout(self.timeout)
+            .request(Method::POST, OFFER_MODIFY_URL)
+            .headers((&*auth_data).try_into()?)
+            .json(&EditData::new(details, auth_data.csrf_token()))
+            .build()?)
+    }
+
+    fn build_conversations_request(&self, page: usize) -> anyhow::Result<Request> {
+        let auth_data = self.auth_data();
+        Ok(self
+            .client
+            .request(Method::GET, build_conversations_url(auth_data.user_id()))
+            .query(&[
+                ("page", page.to_string()),
+                ("limit", CONVERSATIONS_PAGE_SIZE.to_string()),
+            ])
+            .headers((&*auth_data).try_into()?)
+            .build()?)
+    }
+
+    fn build_message_request(&self, conversation: u32, content: &str) -> anyhow::Result<Request> {
+        let auth_data = self.auth_data();
+        Ok(self
+            .client
+            .request(
+                Method::POST,
+                build_messages_url(conversation, auth_data.user_id()),
+            )
+            .headers((&*auth_data).try_into()?)
+            .json(&MessageData::new(content, auth_data.csrf_token()))
+            .build()?)
+    }
+
+    fn build_images_request(&self, id: u32) -> anyhow::Result<Request> {
+        let auth_data = self.auth_data();
+        Ok(self
+            .client
+            .request(Method::GET, build_images_url(id, auth_data.user_id(), None))
+            .headers((&*auth_data).try_into()?)
+            .build()?)
+    }
+
+    fn build_upload_request(&self, id: u32, image: &LocalImage) -> anyhow::Result<Request> {
+        let auth_data = self.auth_data();
+        Ok(self
+            .client
+            .request(
+                Method::POST,
+                build_images_url(id, auth_data.user_id(), None),
+            )
+            .headers((&*auth_data).try_into()?)
+            .multipart(
+                Form::new()
+                    .text("csrf_token", auth_data.csrf_token().to_string())
+                    .part(
+                        "image",
+                        Part::bytes(image.bytes.clone())
+                            .file_name(image.file_name())
+                            .mime_str(image.format.mime_type())?,
+                    ),
+            )
+            .build()?)
+    }
+
+    fn build_delete_image_request(&self, id: u32, image_id: &str) -> anyhow::Result<Request> {
+        let auth_data = self.auth_data();
+        Ok(self
+            .client
+            .request(
+                Method::DELETE,
+                build_images_url(id, auth_data.user_id(), Some(image_id)),
+            )
+            .headers((&*auth_data).try_into()?)
+            .json(&CsrfData::new(auth_data.csrf_token()))
+            .build()?)
+    }
+
+    fn build_arrange_images_request(
+        &self,
+        id: u32,
+        image_ids: &[String],
+    ) -> anyhow::Result<Request> {
+        let auth_data = self.auth_data();
+        Ok(self
+            .client
+            .request(
+                Method::PATCH,
+                build_images_url(id, auth_data.user_id(), None),
+            )
+            .headers((&*auth_data).try_into()?)
+            .json(&ImageOrderData::new(image_ids, auth_data.csrf_token()))
+            .build()?)
+    }
+
+    fn build_details_request(&self, id: u32) -> anyhow::Result<Request> {
+        let auth_data = self.auth_data();
+        Ok(self
+            .client
+            .request(Method::GET, build_patch_url(id, auth_data.user_id()))
+            .headers((&*auth_data).try_into()?)
+            .build()?)
+    }
+
+    fn build_edit_request<T>(&self, id: u32, update: &T) -> anyhow::Result<Request>
+    where
+        T: Serialize,
+    {
+        let auth_data = self.auth_data();
+        Ok(self
+            .client
+            .request(Method::PATCH, build_patch_url(id, auth_data.user_id()))
+            .headers((&*auth_data).try_into()?)
+            .json(&EditData::new(update, auth_data.csrf_token()))
             .build()?)
     }
 }
@@ -95,3 +510,227 @@ fn build_patch_url(offer_id: u32, user_id: &str) -> Url {
     debug!("Patch URL: {url}");
     url
 }
+
+fn build_sessions_url(user_id: &str) -> Url {
+    let mut url = Url::parse(SESSIONS_URL).expect("Default URL should be valid.");
+    url.path_segments_mut()
+        .expect("Path segments should be accessible.")
+        .push(user_id);
+    url
+}
+
+fn build_conversations_url(user_id: &str) -> Url {
+    let mut url = Url::parse(CONVERSATIONS_URL).expect("Default URL should be valid.");
+    url.path_segments_mut()
+        .expect("Path segments should be accessible.")
+        .push("user")
+        .push(user_id);
+    url
+}
+
+fn build_messages_url(conversation: u32, user_id: &str) -> Url {
+    let mut url = Url::parse(CONVERSATIONS_URL).expect("Default URL should be valid.");
+    url.path_segments_mut()
+        .expect("Path segments should be accessible.")
+        .push(&conversation.to_string())
+        .push("users")
+        .push(user_id)
+        .push("messages");
+    url
+}
+
+fn build_images_url(offer_id: u32, user_id: &str, image_id: Option<&str>) -> Url {
+    let mut url = build_patch_url(offer_id, user_id);
+    {
+        let mut segments = url
+            .path_segments_mut()
+            .expect("Path segments should be accessible.");
+        segments.push("images");
+
+        if let Some(image_id) = image_id {
+            segments.push(image_id);
+        }
+    }
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::{Value, json};
+
+    use crate::client::fixture::RecordedRequest;
+    use crate::client::transport::stub::{self, Stub};
+
+    const PATCH_URL: &str = "https://www.wg-gesucht.de/api/offers/42/users/1234567";
+
+    fn requests(stub: &Stub) -> Vec<(String, String)> {
+        stub.requests()
+            .into_iter()
+            .map(|request| (request.method, request.url))
+            .collect()
+    }
+
+    fn header<'request>(request: &'request RecordedRequest, name: &str) -> Option<&'request str> {
+        request
+            .headers
+            .iter()
+            .find(|(candidate, _)| candidate == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn relogin() -> [anyhow::Result<http::Response<String>>; 2] {
+        [
+            stub::login(&["X-Dev-Ref-No=devref456", "X-Access-Token=token456"]),
+            stub::offers_page("csrf5678"),
+        ]
+    }
+
+    #[tokio::test]
+    async fn retry_after_relogin() {
+        let [login, page] = relogin();
+        let stub = Arc::new(Stub::new([
+            stub::status(401),
+            login,
+            page,
+            stub::status(200),
+        ]));
+        stub::session(stub.clone())
+            .activate(42)
+            .await
+            .expect("Retried request should succeed.");
+        assert_eq!(
+            requests(&stub),
+            [
+                ("PATCH", PATCH_URL),
+                (
+                    "POST",
+                    "https://www.wg-gesucht.de/ajax/sessions.php?action=login"
+                ),
+                ("GET", "https://www.wg-gesucht.de/meine-anzeigen.html"),
+                ("PATCH", PATCH_URL),
+            ]
+            .map(|(method, url)| (method.to_string(), url.to_string()))
+        );
+
+        let retry = &stub.requests()[3];
+        assert_eq!(header(retry, "x-authorization"), Some("Bearer token456"));
+        assert_eq!(header(retry, "x-dev-ref-no"), Some("devref456"));
+        assert_eq!(
+            serde_json::from_str::<Value>(
+                retry.body.as_deref().expect("Retry should have a body.")
+            )
+            .expect("Body should be JSON."),
+            json!({"deactivated": "0", "csrf_token": "csrf5678"})
+        );
+    }
+
+    #[tokio::test]
+    async fn second_unauthorized() {
+        let [login, page] = relogin();
+        let stub = Arc::new(Stub::new([
+            stub::status(401),
+            login,
+            page,
+            stub::status(401),
+        ]));
+        let error = stub::session(stub.clone())
+            .activate(42)
+            .await
+            .expect_err("Second 401 should be returned.");
+        assert!(error.to_string().contains("401"));
+        assert_eq!(stub.requests().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn relogin_without_totp_secret() {
+        let stub = Arc::new(Stub::new([
+            stub::status(401),
+            Ok(http::Response::builder()
+                .status(200)
+                .body(r#"{"verification_required": true}"#.to_string())
+                .expect("Response should be valid.")),
+        ]));
+        let error = stub::session_with(stub.clone(), stub::verification_scraping())
+            .activate(42)
+            .await
+            .expect_err("Login should require a TOTP secret.");
+        assert_eq!(
+            error.to_string(),
+            "Login of user@example.com requires a verification code again, which requires a TOTP secret"
+        );
+        assert_eq!(stub.requests().len(), 2);
+    }
+
+    fn conversations(ids: std::ops::Range<u32>) -> anyhow::Result<http::Response<String>> {
+        stub::json(&json!({
+            "_embedded": {
+                "conversations": ids
+                    .map(|id| json!({"conversation_id": id.to_string(), "firstname": "Kim"}))
+                    .collect::<Vec<_>>(),
+            },
+        }))
+    }
+
+    #[tokio::test]
+    async fn conversation_pages() {
+        let stub = Arc::new(Stub::new([conversations(0..100), conversations(100..102)]));
+        let conversations = stub::session(stub.clone())
+            .list_conversations()
+            .await
+            .expect("listed conversations");
+        assert_eq!(
+            conversations
+                .iter()
+                .map(|conversation| conversation.id)
+                .collect::<Vec<_>>(),
+            (0..102).collect::<Vec<_>>()
+        );
+        let urls: Vec<String> = requests(&stub).into_iter().map(|(_, url)| url).collect();
+        assert_eq!(urls.len(), 2);
+        assert!(urls[0].contains("page=1&limit=100"), "{}", urls[0]);
+        assert!(urls[1].contains("page=2&limit=100"), "{}", urls[1]);
+    }
+
+    #[tokio::test]
+    async fn malformed_conversations_are_skipped() {
+        let stub = Arc::new(Stub::new([stub::json(&json!({
+            "_embedded": {
+                "conversations": [
+                    {"conversation_id": "1"},
+                    {"firstname": "Kim"},
+                    {"conversation_id": "not a number"},
+                    "not an object",
+                    {"conversation_id": 2},
+                ],
+            },
+        }))]));
+        let conversations = stub::session(stub)
+            .list_conversations()
+            .await
+            .expect("listed conversations");
+        assert_eq!(
+            conversations
+                .iter()
+                .map(|conversation| conversation.id)
+                .collect::<Vec<_>>(),
+            [1, 2]
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_conversations() {
+        for body in [
+            json!({}),
+            json!({"_embedded": {}}),
+            json!({"_embedded": {"conversations": {}}}),
+        ] {
+            let stub = Arc::new(Stub::new([stub::json(&body)]));
+            assert!(
+                stub::session(stub).list_conversations().await.is_err(),
+                "{body}"
+            );
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use std::env::var_os;
+use std::fmt::Debug;
+use std::pin::Pin;
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard, PoisonError};
+
+use recording::{Recorder, RecordingTransport};
+use replay::Replayer;
+use reqwest::{Request, Response};
+
+mod recording;
+mod replay;
+#[cfg(test)]
+pub mod stub;
+
+/// Environment variable with the directory to record exchanges to.
+const RECORD: &str = "WG_GESUCHT_RECORD";
+/// Environment variable with the directory to replay exchanges from.
+const REPLAY: &str = "WG_GESUCHT_REPLAY";
+
+static RECORDER: LazyLock<Option<Recorder>> =
+    LazyLock::new(|| var_os(RECORD).map(|dir| Recorder::new(dir.into())));
+static REPLAYER: LazyLock<Option<Arc<Replayer>>> =
+    LazyLock::new(|| var_os(REPLAY).map(|dir| Arc::new(Replayer::new(dir.into()))));
+
+/// Future resolving to the response of a request.
+pub type ResponseFuture<'transport> =
+    Pin<Box<dyn Future<Output = anyhow::Result<Response>> + Send + 'transport>>;
+
+/// Executes HTTP requests on behalf of [`Client`](super::Client) and [`Session`](super::Session).
+pub trait Transport: Debug + Send + Sync {
+    /// Execute a request.
+    ///
+    /// # Errors
+    ///
+    /// The future resolves to an [`anyhow::Error`] if no response could be obtained.
+    fn execute(&self, request: Request) -> ResponseFuture<'_>;
+}
+
+/// Sends requests via HTTP using `reqwest` with its own cookie store.
+#[derive(Debug)]
+pub struct HttpTransport(reqwest::Client);
+
+impl Default for HttpTransport {
+    #[allow(clippy::missing_panics_doc)]
+    fn default() -> Self {
+        Self(
+            reqwest::Client::builder()
+                .cookie_store(true)
+                .build()
+                .expect("Client builder should never fail."),
+        )
+    }
+}
+
+impl Transport for HttpTransport {
+    fn execute(&self, request: Request) -> ResponseFuture<'_> {
+        Box::pin(async move { Ok(self.0.execute(request).await?) })
+    }
+}
+
+/// Return the transport as configured by the environment.
+///
+/// Requests are answered from fixtures if `WG_GESUCHT_REPLAY` is set, recorded to fixtures
+/// if `WG_GESUCHT_RECORD` is set and otherwise sent via HTTP.
+#[must_use]
+pub fn from_env() -> Arc<dyn Transport> {
+    match (REPLAYER.as_ref(), RECORDER.as_ref()) {
+        (Some(replayer), _) => replayer.clone(),
+        (None, Some(recorder)) => Arc::new(RecordingTransport::new(recorder)),
+        (None, None) => Arc::new(HttpTransport::default()),
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+
+    use reqwest::Request;
+    use serde_json::{Value, json};
+
+    use super::replay::Replayer;
+    use super::{ResponseFuture, Transport, lock};
+    use crate::client::fixture::RecordedRequest;
+    use crate::client::{Client, TIMEOUT};
+    use crate::config_file::Scraping;
+    use crate::credentials::{Credentials, Verification};
+
+    const USER_AGENT: &str = "wg_gesucht_updater/test";
+
+    /// Replays fixtures and keeps the unredacted requests.
+    #[derive(Debug)]
+    struct Capture {
+        replayer: Replayer,
+        requests: Mutex<Vec<RecordedRequest>>,
+    }
+
+    impl Transport for Capture {
+        fn execute(&self, request: Request) -> ResponseFuture<'_> {
+            lock(&self.requests).push(RecordedRequest::from(&request));
+            self.replayer.execute(request)
+        }
+    }
+
+    fn body(request: &RecordedRequest) -> Value {
+        serde_json::from_str(
+            request
+                .body
+                .as_deref()
+                .expect("Request should have a body."),
+        )
+        .expect("Body should be JSON.")
+    }
+
+    #[tokio::test]
+    async fn replay_bump() {
+        let transport = Arc::new(Capture {
+            replayer: Replayer::new(Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/bump")),
+            requests: Mutex::new(Vec::new()),
+        });
+        let session = Client::with_transport(
+            TIMEOUT,
+            Cow::Borrowed(USER_AGENT),
+            Scraping::default(),
+            transport.clone(),
+        )
+        .login(Credentials::new(
+            "user@example.com".to_string(),
+            "hunter22".to_string(),
+            true,
+            Verification::Prompt,
+        ))
+        .await
+        .expect("Login should be replayed.");
+        let offers = session.offers().await.expect("Offers should be replayed.");
+        assert_eq!(
+            offers.iter().map(|offer| offer.id).collect::<Vec<_>>(),
+            [42]
+        );
+        session.bump(42).await.expect("Bump should be replayed.");
+
+        let requests = lock(&transport.requests).clone();
+        assert_eq!(requests.len(), 5);
+        assert_eq!(
+            body(&requests[0]),
+            json!({
+                "login_email_username": "user@example.com",
+                "login_password": "hunter22",
+                "login_form_autologin": "1",
+                "display_language": "de",
+            })
+        );
+
+        for (request, deactivated) in requests[3..].iter().zip(["1", "0"]) {
+            assert_eq!(request.method, "PATCH");
+            assert_eq!(
+                request.url,
+                "https://www.wg-gesucht.de/api/offers/42/users/1234567"
+            );
+            assert_eq!(
+                request.headers,
+                [
+                    ("content-type", "application/json"),
+                    ("user-agent", USER_AGENT),
+                    ("x-authorization", "Bearer REDACTED"),
+                    ("x-client-id", "wg_desktop_website"),
+                    ("x-dev-ref-no", "REDACTED"),
+                    ("x-user-id", "1234567"),
+                ]
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+            );
+            assert_eq!(
+                body(request),
+                json!({"deactivated": deactivated, "csrf_token": "REDACTED"})
+            );
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::fs::create_dir_all;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::anyhow;
+use log::info;
+use reqwest::{Request, Response};
+use serde_rw::ToFile;
+
+use super::{HttpTransport, ResponseFuture, Transport, lock};
+use crate::client::fixture::{Exchange, RecordedRequest, RecordedResponse, Redactor};
+
+/// Sends requests via HTTP and records them along with their responses.
+#[derive(Debug)]
+pub struct RecordingTransport {
+    inner: HttpTransport,
+    recorder: &'static Recorder,
+}
+
+impl RecordingTransport {
+    /// Create a new transport that records to the given recorder.
+    #[must_use]
+    pub fn new(recorder: &'static Recorder) -> Self {
+        Self {
+            inner: HttpTransport::default(),
+            recorder,
+        }
+    }
+
+    async fn record(&self, request: Request) -> anyhow::Result<Response> {
+        let captured = RecordedRequest::from(&request);
+        let response = self.inner.execute(request).await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+        self.recorder.record(Exchange {
+            request: captured,
+            response: RecordedResponse::new(status, &headers, &body),
+        })?;
+        let mut builder = http::Response::builder().status(status);
+
+        if let Some(builder_headers) = builder.headers_mut() {
+            *builder_headers = headers;
+        }
+
+        Ok(builder.body(body)?.into())
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn execute(&self, request: Request) -> ResponseFuture<'_> {
+        Box::pin(self.record(request))
+    }
+}
+
+/// Records exchanges to numbered fixture files with secrets redacted.
+#[derive(Debug)]
+pub struct Recorder {
+    dir: PathBuf,
+    state: Mutex<(Redactor, Vec<Exchange>)>,
+}
+
+impl Recorder {
+    /// Create a recorder writing to the given directory.
+    #[must_use]
+    pub const fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            state: Mutex::new((Redactor::new(), Vec::new())),
+        }
+    }
+
+    /// Record an exchange.
+    ///
+    /// Since secrets may only be learned from later exchanges,
+    /// all fixture files are rewritten with the secrets known so far.
+    fn record(&self, exchange: Exchange) -> anyhow::Result<()> {
+        let mut state = lock(&self.state);
+        let (redactor, exchanges) = &mut *state;
+        redactor.learn_request(&exchange.request);
+        redactor.learn_response(&exchange.response);
+        exchanges.push(exchange);
+        create_dir_all(&self.dir)?;
+
+        for (index, exchange) in exchanges.iter().enumerate() {
+            redactor
+                .redact(exchange)
+                .write_to_file_pretty(self.dir.join(file_name(index)))
+                .map_err(|error| anyhow!("{error}"))?;
+        }
+
+        let count = exchanges.len();
+        drop(state);
+        info!("Recorded exchange {count} to {}", self.dir.display());
+        Ok(())
+    }
+}
+
+fn file_name(index: usize) -> String {
+    format!("{:04}.json", index + 1)
+}
@@ -0,0 +1,100 @@
+use std::collections::VecDeque;
+use std::fs::read_dir;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::anyhow;
+use log::debug;
+use reqwest::{Request, Response};
+use serde_rw::FromFile;
+
+use super::{ResponseFuture, Transport, lock};
+use crate::client::fixture::{Exchange, RecordedRequest, Redactor};
+
+/// Answers requests with previously recorded exchanges in their order.
+#[derive(Debug)]
+pub struct Replayer {
+    dir: PathBuf,
+    state: Mutex<Option<(Redactor, VecDeque<Exchange>)>>,
+}
+
+impl Replayer {
+    /// Create a replayer reading the fixtures from the given directory.
+    #[must_use]
+    pub const fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            state: Mutex::new(None),
+        }
+    }
+
+    /// Answer the request with the next recorded exchange.
+    ///
+    /// The request is redacted like the recorded one and must match it exactly.
+    fn replay(&self, request: &RecordedRequest) -> anyhow::Result<Response> {
+        let (request, exchange) = self.next(request)?;
+
+        if request != exchange.request {
+            return Err(anyhow!(
+                "Request does not match fixture:\nexpected: {:?}\nactual:   {request:?}",
+                exchange.request
+            ));
+        }
+
+        debug!("Replaying {} {}", request.method, request.url);
+        let mut builder = http::Response::builder().status(exchange.response.status);
+
+        for (name, value) in &exchange.response.headers {
+            builder = builder.header(name, value);
+        }
+
+        Ok(builder.body(exchange.response.body)?.into())
+    }
+
+    /// Redact the request and return it along with the next recorded exchange.
+    fn next(&self, request: &RecordedRequest) -> anyhow::Result<(RecordedRequest, Exchange)> {
+        let mut state = lock(&self.state);
+
+        if state.is_none() {
+            *state = Some((Redactor::new(), self.load()?));
+        }
+
+        let Some((redactor, exchanges)) = state.as_mut() else {
+            return Err(anyhow!("Fixtures are not loaded"));
+        };
+        redactor.learn_request(request);
+        let request = redactor.redact_request(request);
+        let exchange = exchanges.pop_front();
+        drop(state);
+        let exchange = exchange.ok_or_else(|| {
+            anyhow!(
+                "Unexpected request {} {}: no more fixtures",
+                request.method,
+                request.url
+            )
+        })?;
+        Ok((request, exchange))
+    }
+
+    fn load(&self) -> anyhow::Result<VecDeque<Exchange>> {
+        let mut paths: Vec<PathBuf> = read_dir(&self.dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<_, _>>()?;
+        paths.retain(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        });
+        paths.sort();
+        paths
+            .iter()
+            .map(|path| Exchange::from_file(path).map_err(|error| anyhow!("{error}")))
+            .collect()
+    }
+}
+
+impl Transport for Replayer {
+    fn execute(&self, request: Request) -> ResponseFuture<'_> {
+        let response = self.replay(&RecordedRequest::from(&request));
+        Box::pin(async move { response })
+    }
+}
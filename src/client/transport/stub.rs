@@ -0,0 +1,138 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use anyhow::anyhow;
+use reqwest::{Request, Response};
+
+use super::{ResponseFuture, Transport, lock};
+use crate::auth_data::AuthData;
+use crate::client::fixture::RecordedRequest;
+use crate::client::{Client, Session, TIMEOUT, USER_AGENT};
+use crate::config_file::Scraping;
+use crate::credentials::{Credentials, Verification};
+
+/// Verification endpoint configured by [`verification_scraping`].
+pub const VERIFICATION_URL: &str = "https://www.wg-gesucht.de/ajax/sessions.php?action=verify";
+
+/// Answers requests with canned responses in their order and keeps the requests.
+#[derive(Debug, Default)]
+pub struct Stub {
+    replies: Mutex<VecDeque<anyhow::Result<http::Response<String>>>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl Stub {
+    /// Create a stub answering with the given replies.
+    #[must_use]
+    pub fn new(replies: impl IntoIterator<Item = anyhow::Result<http::Response<String>>>) -> Self {
+        Self {
+            replies: Mutex::new(replies.into_iter().collect()),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Return the requests executed so far.
+    #[must_use]
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        lock(&self.requests).clone()
+    }
+}
+
+impl Transport for Stub {
+    fn execute(&self, request: Request) -> ResponseFuture<'_> {
+        let request = RecordedRequest::from(&request);
+        let reply = lock(&self.replies).pop_front().unwrap_or_else(|| {
+            Err(anyhow!(
+                "Unexpected request {} {}: no more replies",
+                request.method,
+                request.url
+            ))
+        });
+        lock(&self.requests).push(request);
+        Box::pin(async move { Ok(Response::from(reply?)) })
+    }
+}
+
+/// Return a session of user 1234567 that executes its requests via the stub.
+pub fn session(stub: Arc<Stub>) -> Session {
+    session_with(stub, Scraping::default())
+}
+
+/// Return a client that executes its requests via the stub.
+pub fn client(stub: Arc<Stub>, scraping: Scraping) -> Client {
+    Client::with_transport(TIMEOUT, Cow::Borrowed(USER_AGENT), scraping, stub)
+}
+
+/// Return the credentials of user 1234567.
+pub fn credentials(verification: Verification) -> Credentials {
+    Credentials::new(
+        "user@example.com".to_string(),
+        "hunter22".to_string(),
+        true,
+        verification,
+    )
+}
+
+/// Return a session of user 1234567 that executes its requests via the stub
+/// and scrapes pages as configured.
+pub fn session_with(stub: Arc<Stub>, scraping: Scraping) -> Session {
+    Session::new(
+        client(stub, scraping),
+        credentials(Verification::Prompt),
+        AuthData::new(
+            "1234567".into(),
+            "wg_desktop_website".into(),
+            "token123".into(),
+            "devref123".into(),
+            "csrf1234".into(),
+        ),
+    )
+}
+
+/// Return the default scraping strategies with a verification endpoint configured.
+pub fn verification_scraping() -> Scraping {
+    serde_json::from_value(serde_json::json!({"verification": {"url": VERIFICATION_URL}}))
+        .expect("Scraping should be valid.")
+}
+
+/// Reply with an empty body and the given status.
+pub fn status(status: u16) -> anyhow::Result<http::Response<String>> {
+    Ok(http::Response::builder()
+        .status(status)
+        .body(String::new())?)
+}
+
+/// Reply with the given JSON body.
+pub fn json(body: &serde_json::Value) -> anyhow::Result<http::Response<String>> {
+    Ok(http::Response::builder()
+        .status(200)
+        .header("content-type", "application/json")
+        .body(body.to_string())?)
+}
+
+/// Reply to a login request with the given cookies.
+pub fn login(cookies: &[&str]) -> anyhow::Result<http::Response<String>> {
+    let mut builder = http::Response::builder().status(200);
+
+    for cookie in cookies {
+        builder = builder.header("set-cookie", format!("{cookie}; path=/"));
+    }
+
+    Ok(builder.body("{}".to_string())?)
+}
+
+/// Reply with the offers page of user 1234567 carrying the given CSRF token.
+pub fn offers_page(csrf_token: &str) -> anyhow::Result<http::Response<String>> {
+    Ok(http::Response::builder()
+        .status(200)
+        .header("content-type", "text/html; charset=UTF-8")
+        .body(format!(
+            r#"<a href="/logout" data-csrf_token="{csrf_token}" data-user_id="1234567">Logout</a>"#
+        ))?)
+}
+
+/// Fail like a request that timed out.
+pub fn timeout() -> anyhow::Result<http::Response<String>> {
+    Err(anyhow!("operation timed out"))
+}
@@ -0,0 +1,210 @@
+use std::io::{Write, stdin, stdout};
+
+use anyhow::anyhow;
+
+use crate::offer_ref::OfferRef;
+use crate::settings::Settings;
+
+pub mod applicants;
+pub mod auto_reply;
+pub mod diagnose;
+pub mod edit;
+pub mod export;
+pub mod import;
+pub mod init;
+pub mod messages;
+pub mod photos;
+pub mod rank;
+pub mod stats;
+pub mod sync_texts;
+pub mod tui;
+pub mod viewings;
+pub mod watch;
+
+/// Select the settings of the account that manages the given offer.
+///
+/// If an account is given explicitly, its settings are returned regardless of the offer.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if no or more than one account matches.
+pub fn select_account(
+    settings: Vec<Settings>,
+    account: Option<&str>,
+    offer: u32,
+) -> anyhow::Result<Settings> {
+    let mut candidates: Vec<Settings> = settings
+        .into_iter()
+        .filter(|settings| {
+            account.map_or_else(
+                || settings.offers().contains(&offer),
+                |account| settings.user_name() == account,
+            )
+        })
+        .collect();
+
+    match (candidates.pop(), candidates.is_empty()) {
+        (Some(settings), true) => Ok(settings),
+        (Some(_), false) => Err(anyhow!(
+            "Offer {offer} is configured for multiple accounts, please specify --account"
+        )),
+        (None, _) => Err(account.map_or_else(
+            || anyhow!("Offer {offer} is not configured for any account, please specify --account"),
+            |account| anyhow!("No such account: {account}"),
+        )),
+    }
+}
+
+/// Return the ID of the referenced offer.
+///
+/// Names, groups and tag expressions are looked up in the catalog of the given account or of all accounts if none is given.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the reference cannot be resolved in the considered accounts,
+/// can be resolved in more than one of them or does not refer to exactly one offer.
+pub fn resolve_offer(
+    settings: &[Settings],
+    account: Option<&str>,
+    offer: &OfferRef,
+) -> anyhow::Result<u32> {
+    if let OfferRef::Id(id) = offer {
+        return Ok(*id);
+    }
+
+    let resolved: Vec<(&str, Vec<u32>)> = settings
+        .iter()
+        .filter(|settings| account.is_none_or(|account| settings.user_name() == account))
+        .filter_map(|settings| {
+            settings
+                .catalog()
+                .resolve(offer)
+                .ok()
+                .map(|ids| (settings.user_name(), ids))
+        })
+        .collect();
+
+    match resolved.as_slice() {
+        [] => Err(anyhow!("No offer {offer}")),
+        [(_, ids)] => match ids.as_slice() {
+            [id] => Ok(*id),
+            _ => Err(anyhow!(
+                "{offer} refers to {} offers instead of one",
+                ids.len()
+            )),
+        },
+        _ => Err(anyhow!(
+            "{offer} is ambiguous, it is configured in the accounts {}, please specify --account",
+            resolved
+                .iter()
+                .map(|(user_name, _)| *user_name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Return the settings of the given account or of all accounts if none is given.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the given account is not configured.
+pub fn filter_accounts(
+    settings: Vec<Settings>,
+    account: Option<&str>,
+) -> anyhow::Result<Vec<Settings>> {
+    let Some(account) = account else {
+        return Ok(settings);
+    };
+    let settings: Vec<Settings> = settings
+        .into_iter()
+        .filter(|settings| settings.user_name() == account)
+        .collect();
+
+    if settings.is_empty() {
+        Err(anyhow!("No such account: {account}"))
+    } else {
+        Ok(settings)
+    }
+}
+
+/// Ask the user for confirmation on the terminal.
+///
+/// # Errors
+///
+/// Return an [`std::io::Error`] if reading from or writing to the terminal failed.
+pub fn confirm(prompt: &str) -> std::io::Result<bool> {
+    print!("{prompt} [y/N] ");
+    stdout().flush()?;
+    let mut answer = String::new();
+    stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes" | "Yes"))
+}
+
+/// Ask the user for a line of input on the terminal.
+///
+/// # Errors
+///
+/// Return an [`std::io::Error`] if reading from or writing to the terminal failed.
+pub fn prompt(prompt: &str) -> std::io::Result<String> {
+    print!("{prompt}");
+    stdout().flush()?;
+    let mut answer = String::new();
+    stdin().read_line(&mut answer)?;
+    Ok(answer.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::resolve_offer;
+    use crate::config_file::Account;
+    use crate::offer_ref::OfferRef;
+    use crate::settings::Settings;
+
+    fn settings(user_name: &str, offers: &serde_json::Value) -> Settings {
+        serde_json::from_value::<Account>(json!({
+            "user_name": user_name,
+            "password": "secret",
+            "offers": offers,
+        }))
+        .expect("Account should be valid.")
+        .into()
+    }
+
+    #[test]
+    fn resolve_ambiguous_name() {
+        let settings = [
+            settings("a@example.com", &json!([{"id": 1, "name": "altona"}])),
+            settings(
+                "b@example.com",
+                &json!([{"id": 2, "name": "altona"}, {"id": 3, "name": "ottensen"}]),
+            ),
+        ];
+        let altona = OfferRef::Name("altona".to_string());
+
+        assert_eq!(
+            resolve_offer(&settings, None, &altona)
+                .expect_err("Name should be ambiguous.")
+                .to_string(),
+            "\"altona\" is ambiguous, it is configured in the accounts a@example.com, b@example.com, please specify --account"
+        );
+        assert_eq!(
+            resolve_offer(&settings, Some("b@example.com"), &altona).expect("Name should resolve."),
+            2
+        );
+        assert_eq!(
+            resolve_offer(&settings, None, &OfferRef::Name("ottensen".to_string()))
+                .expect("Name should resolve."),
+            3
+        );
+        assert_eq!(
+            resolve_offer(&settings, None, &OfferRef::Id(4)).expect("ID should resolve."),
+            4
+        );
+        assert!(
+            resolve_offer(&settings, None, &OfferRef::Name("eimsbuettel".to_string())).is_err()
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use csv::Writer;
+use log::info;
+use serde::Serialize;
+
+use super::filter_accounts;
+use crate::offer_ref::OfferRef;
+use crate::settings::Settings;
+
+/// A row of the applicants export.
+#[derive(Debug, Serialize)]
+struct Applicant<'data> {
+    account: &'data str,
+    offer: u32,
+    name: &'data str,
+    first_contact: Option<DateTime<Utc>>,
+    message: &'data str,
+    profile: Option<&'data str>,
+}
+
+/// Export the applicants of the configured offers to a CSV file.
+///
+/// If any offers are selected, the applicants of these offers are exported instead.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the config file could not be read, a login failed,
+/// the conversations could not be fetched or the CSV file could not be written.
+pub async fn run(
+    config_file: &Path,
+    account: Option<&str>,
+    selectors: &[OfferRef],
+    output: &Path,
+) -> anyhow::Result<()> {
+    let mut writer = Writer::from_path(output)?;
+
+    for settings in filter_accounts(Settings::load(config_file)?, account)? {
+        let offers = if selectors.is_empty() {
+            settings.offers()
+        } else {
+            settings.catalog().select(selectors)?.into_iter().collect()
+        };
+        let mut conversations = settings
+            .with_session(async |session| session.list_conversations().await)
+            .await?;
+        conversations.sort_by_key(|conversation| (conversation.offer, conversation.first_contact));
+
+        for conversation in &conversations {
+            let Some(offer) = conversation.offer.filter(|offer| offers.contains(offer)) else {
+                continue;
+            };
+
+            writer.serialize(Applicant {
+                account: settings.user_name(),
+                offer,
+                name: &conversation.sender,
+                first_contact: conversation.first_contact,
+                message: &conversation.first_message,
+                profile: conversation.profile.as_deref(),
+            })?;
+        }
+
+        info!(
+            "Exported applicants of {} offers of {}",
+            offers.len(),
+            settings.user_name()
+        );
+    }
+
+    writer.flush()?;
+    Ok(())
+}
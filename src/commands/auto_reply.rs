@@ -0,0 +1,285 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::anyhow;
+use chrono::{DateTime, Local, Utc};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+use crate::config_file::{Account, AutoReply, ConfigFile};
+use crate::conversation::Conversation;
+use crate::settings::Settings;
+use crate::state::{PersistentState, state_dir};
+
+/// Conversations that have been replied to automatically, per account.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct RepliedConversations(BTreeMap<String, BTreeMap<u32, DateTime<Utc>>>);
+
+impl RepliedConversations {
+    fn replied(&self, account: &str, conversation: u32) -> bool {
+        self.0
+            .get(account)
+            .is_some_and(|replied| replied.contains_key(&conversation))
+    }
+
+    /// On the first run of an account, mark its existing conversations about the configured
+    /// offers as replied and return their number.
+    fn seed(
+        &mut self,
+        account: &str,
+        auto_reply: &AutoReply,
+        conversations: &[Conversation],
+        now: DateTime<Utc>,
+    ) -> Option<usize> {
+        if self.0.contains_key(account) {
+            return None;
+        }
+
+        let replied = self.0.entry(account.to_string()).or_default();
+
+        for conversation in conversations {
+            if conversation
+                .offer
+                .is_some_and(|offer| auto_reply.offers.contains(&offer))
+            {
+                replied.insert(conversation.id, now);
+            }
+        }
+
+        Some(replied.len())
+    }
+
+    /// Return the configured offer of a conversation that awaits an automatic reply.
+    ///
+    /// A conversation awaits a reply if it has not been replied to before
+    /// and its last message was not sent by the account (`user_id`) itself.
+    fn awaiting_reply(
+        &self,
+        account: &str,
+        user_id: &str,
+        auto_reply: &AutoReply,
+        conversation: &Conversation,
+    ) -> Option<u32> {
+        conversation
+            .offer
+            .filter(|offer| auto_reply.offers.contains(offer))
+            .filter(|_| {
+                !self.replied(account, conversation.id)
+                    && conversation.last_sender.as_deref() != Some(user_id)
+            })
+    }
+}
+
+impl PersistentState for RepliedConversations {
+    const FILE_NAME: &'static str = "auto_replies.json";
+}
+
+/// Send the templated first reply to applicants of the configured offers.
+///
+/// A conversation is replied to if it has not been replied to automatically before
+/// and its last message was not sent by the account itself.
+/// On the first run of an account, its existing conversations are only recorded, since they may
+/// have been answered by hand before.
+/// Conversations whose reply cannot be rendered are logged and skipped.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the config file could not be read, a login failed
+/// or the conversations could not be read or replied to.
+pub async fn run(config_file: &Path, account: Option<&str>, dry_run: bool) -> anyhow::Result<()> {
+    let config_file = ConfigFile::load(config_file)?;
+    let state_dir = state_dir(config_file.state_dir.clone());
+    let mut state = RepliedConversations::load(&state_dir);
+    let accounts: Vec<&Account> = config_file
+        .accounts
+        .iter()
+        .filter(|candidate| account.is_none_or(|account| candidate.user_name == account))
+        .collect();
+
+    if let (Some(account), true) = (account, accounts.is_empty()) {
+        return Err(anyhow!("No such account: {account}"));
+    }
+
+    let now = Local::now().naive_local();
+
+    for account in accounts {
+        let Some(auto_reply) = &account.auto_reply else {
+            continue;
+        };
+        Settings::from_config(&config_file, account)
+            .with_session(async |session| {
+                let titles: BTreeMap<u32, Option<String>> = session
+                    .offers()
+                    .await?
+                    .into_iter()
+                    .map(|offer| (offer.id, offer.title))
+                    .collect();
+
+                let conversations = session.list_conversations().await?;
+
+                if let Some(count) =
+                    state.seed(&account.user_name, auto_reply, &conversations, Utc::now())
+                {
+                    info!(
+                        "Marked {count} existing conversations of {} as replied",
+                        account.user_name
+                    );
+
+                    if !dry_run {
+                        state.save(&state_dir)?;
+                    }
+
+                    return Ok(());
+                }
+
+                let user_id = session.user_id();
+
+                for conversation in conversations {
+                    let Some(offer) = state.awaiting_reply(
+                        &account.user_name,
+                        &user_id,
+                        auto_reply,
+                        &conversation,
+                    ) else {
+                        continue;
+                    };
+
+                    let reply = match auto_reply.render(
+                        &config_file.templates,
+                        &conversation,
+                        titles.get(&offer).and_then(Option::as_deref),
+                        now,
+                    ) {
+                        Ok(reply) => reply,
+                        Err(error) => {
+                            error!(
+                                "Could not render the reply to conversation {}: {error}",
+                                conversation.id
+                            );
+                            continue;
+                        }
+                    };
+                    println!(
+                        "Reply to {} on offer {offer} (conversation {}):\n{reply}",
+                        conversation.sender, conversation.id
+                    );
+
+                    if dry_run {
+                        continue;
+                    }
+
+                    info!("Replying to conversation {}", conversation.id);
+                    session.send_message(conversation.id, &reply).await?;
+                    state
+                        .0
+                        .entry(account.user_name.clone())
+                        .or_default()
+                        .insert(conversation.id, Utc::now());
+                    state.save(&state_dir)?;
+                }
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use super::RepliedConversations;
+    use crate::config_file::AutoReply;
+    use crate::conversation::Conversation;
+
+    const ACCOUNT: &str = "user@example.com";
+    const USER_ID: &str = "1234567";
+
+    fn auto_reply() -> AutoReply {
+        AutoReply {
+            offers: vec![42],
+            template: "reply".to_string(),
+            casting_dates: Vec::new(),
+            variables: [].into(),
+        }
+    }
+
+    fn conversation(id: u32, offer: Option<u32>, last_sender: Option<&str>) -> Conversation {
+        Conversation {
+            id,
+            offer,
+            sender: "Kim".to_string(),
+            profile: None,
+            first_message: "Hallo!".to_string(),
+            first_contact: None,
+            timestamp: None,
+            unread: true,
+            last_sender: last_sender.map(ToString::to_string),
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).expect("valid timestamp")
+    }
+
+    #[test]
+    fn seed() {
+        let mut state = RepliedConversations::default();
+        let conversations = [
+            conversation(1, Some(42), None),
+            conversation(2, Some(43), None),
+            conversation(3, None, None),
+            conversation(4, Some(42), Some(USER_ID)),
+        ];
+        assert_eq!(
+            state.seed(ACCOUNT, &auto_reply(), &conversations, now()),
+            Some(2)
+        );
+        assert!(state.replied(ACCOUNT, 1));
+        assert!(!state.replied(ACCOUNT, 2));
+        assert!(state.replied(ACCOUNT, 4));
+        assert_eq!(
+            state.seed(
+                ACCOUNT,
+                &auto_reply(),
+                &[conversation(5, Some(42), None)],
+                now()
+            ),
+            None
+        );
+        assert!(!state.replied(ACCOUNT, 5));
+        assert_eq!(
+            state.seed("other@example.com", &auto_reply(), &[], now()),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn awaiting_reply() {
+        let mut state = RepliedConversations::default();
+        state.seed(
+            ACCOUNT,
+            &auto_reply(),
+            &[conversation(1, Some(42), None)],
+            now(),
+        );
+
+        for (conversation, expected) in [
+            (conversation(1, Some(42), None), None),
+            (conversation(2, Some(42), None), Some(42)),
+            (conversation(2, Some(42), Some("7654321")), Some(42)),
+            (conversation(2, Some(42), Some(USER_ID)), None),
+            (conversation(2, Some(43), None), None),
+            (conversation(2, None, None), None),
+        ] {
+            assert_eq!(
+                state.awaiting_reply(ACCOUNT, USER_ID, &auto_reply(), &conversation),
+                expected,
+                "{conversation:?}"
+            );
+        }
+    }
+}
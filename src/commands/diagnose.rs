@@ -0,0 +1,31 @@
+use std::fs::read_to_string;
+use std::path::Path;
+
+use scraper::Html;
+
+use crate::config_file::{ConfigFile, Strategy};
+use crate::html_ext::HtmlExt;
+
+/// Try the configured scraping strategies against a saved offers page and report their results.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the config file or the page could not be read.
+pub fn run(config_file: &Path, page: &Path) -> anyhow::Result<()> {
+    let scraping = ConfigFile::load(config_file)?.scraping;
+    let html = Html::parse_document(&read_to_string(page)?);
+    report(&html, "CSRF token", &scraping.csrf_token);
+    report(&html, "User ID", &scraping.user_id);
+    Ok(())
+}
+
+fn report(html: &Html, name: &str, strategies: &[Strategy]) {
+    println!("{name}:");
+
+    for strategy in strategies {
+        match html.scrape_strategy(strategy) {
+            Some(value) => println!("  + {strategy}: {value}"),
+            None => println!("  - {strategy}: not found"),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::fs::read_to_string;
+
+use anyhow::anyhow;
+use log::info;
+
+use super::{confirm, resolve_offer, select_account};
+use crate::args::EditArgs;
+use crate::client::Session;
+use crate::offer_update::OfferUpdate;
+use crate::settings::Settings;
+
+/// Edit the details of an offer after previewing the changes.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the account could not be selected,
+/// the login failed or the offer could not be read or updated.
+pub async fn run(args: EditArgs) -> anyhow::Result<()> {
+    let update = OfferUpdate::try_from(&args)?;
+
+    if update.is_empty() {
+        return Err(anyhow!("No changes specified"));
+    }
+
+    let settings = Settings::load(&args.config_file)?;
+    let offer = resolve_offer(&settings, args.account.as_deref(), &args.offer)?;
+    let settings = select_account(settings, args.account.as_deref(), offer)?;
+    let label = settings.catalog().label(offer);
+    settings
+        .with_session(async |session| {
+            edit(session, offer, &label, &update, || {
+                Ok(!args.dry_run && (args.yes || confirm("Apply these changes?")?))
+            })
+            .await
+        })
+        .await
+}
+
+/// Preview the changes of the update to an offer and send them if `apply` agrees.
+async fn edit(
+    session: &Session,
+    offer: u32,
+    label: &str,
+    update: &OfferUpdate,
+    apply: impl FnOnce() -> anyhow::Result<bool>,
+) -> anyhow::Result<()> {
+    let changes = update.diff(&session.offer_details(offer).await?);
+
+    if changes.is_empty() {
+        println!("Offer {label} is up to date.");
+        return Ok(());
+    }
+
+    println!("Changes to offer {label}:");
+    for change in &changes {
+        print!("{change}");
+    }
+
+    if !apply()? {
+        return Ok(());
+    }
+
+    info!("Editing offer: {label}");
+    session.edit(offer, update).await?;
+    Ok(())
+}
+
+impl TryFrom<&EditArgs> for OfferUpdate {
+    type Error = std::io::Error;
+
+    fn try_from(args: &EditArgs) -> Result<Self, Self::Error> {
+        Ok(Self {
+            rent_costs: args.rent,
+            utility_costs: args.utilities,
+            other_costs: args.other_costs,
+            bond_costs: args.bond,
+            property_size: args.size,
+            available_from_date: args.available_from,
+            available_to_date: args.available_until,
+            freetext_property_description: match &args.description_file {
+                Some(file) => Some(read_to_string(file)?),
+                None => args.description.clone(),
+            },
+            ..Self::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::{Value, json};
+
+    use super::edit;
+    use crate::client::transport::stub::{self, Stub};
+    use crate::offer_update::OfferUpdate;
+
+    const UPDATE: OfferUpdate = OfferUpdate {
+        rent_costs: Some(480),
+        utility_costs: None,
+        other_costs: None,
+        bond_costs: None,
+        property_size: None,
+        available_from_date: None,
+        available_to_date: None,
+        freetext_property_description: None,
+        freetext_area_description: None,
+        freetext_flatshare: None,
+        freetext_other: None,
+    };
+
+    async fn requests(rent: &str, apply: bool) -> Vec<(String, Option<Value>)> {
+        let stub = Arc::new(Stub::new([
+            stub::json(&json!({"rent_costs": rent})),
+            stub::status(200),
+        ]));
+        edit(&stub::session(stub.clone()), 42, "#42", &UPDATE, || {
+            Ok(apply)
+        })
+        .await
+        .expect("edited offer");
+        stub.requests()
+            .into_iter()
+            .map(|request| {
+                (
+                    request.method,
+                    request
+                        .body
+                        .map(|body| serde_json::from_str(&body).expect("JSON body")),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn declined_changes_are_not_sent() {
+        assert_eq!(requests("400", false).await, [("GET".to_string(), None)]);
+    }
+
+    #[tokio::test]
+    async fn confirmed_changes_are_sent() {
+        let requests = requests("400", true).await;
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].0, "PATCH");
+        assert_eq!(
+            requests[1]
+                .1
+                .as_ref()
+                .and_then(|body| body.get("rent_costs")),
+            Some(&json!(480))
+        );
+    }
+
+    #[tokio::test]
+    async fn unchanged_offers_are_not_confirmed() {
+        let stub = Arc::new(Stub::new([stub::json(&json!({"rent_costs": "480"}))]));
+        edit(&stub::session(stub.clone()), 42, "#42", &UPDATE, || {
+            panic!("Confirmation requested for an unchanged offer")
+        })
+        .await
+        .expect("checked offer");
+        assert_eq!(stub.requests().len(), 1);
+    }
+}
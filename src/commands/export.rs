@@ -0,0 +1,77 @@
+use std::path::Path;
+
+use anyhow::anyhow;
+use log::info;
+use serde_rw::ToFile;
+
+use super::filter_accounts;
+use crate::backup::{AccountBackup, Backup, OfferBackup};
+use crate::offer_ref::OfferRef;
+use crate::settings::Settings;
+
+/// Export all offers of the configured accounts including their details into a backup file.
+///
+/// The format of the backup file is determined by its extension, i.e. `.json` or `.toml`.
+/// If any offers are given, only those are exported and all of them must be on the offers page.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if a login failed, the offers could not be fetched,
+/// a selected offer is missing or the backup file could not be written.
+pub async fn run(
+    config_file: &Path,
+    account: Option<&str>,
+    offers: &[OfferRef],
+    output: &Path,
+) -> anyhow::Result<()> {
+    let mut accounts = Vec::new();
+
+    for settings in filter_accounts(Settings::load(config_file)?, account)? {
+        let selected = settings.catalog().select(offers)?;
+        let backups = settings
+            .with_session(async |session| {
+                let offers_page = session.offers().await?;
+                let missing: Vec<String> = selected
+                    .iter()
+                    .filter(|&&id| offers_page.iter().all(|offer| offer.id != id))
+                    .map(|&id| settings.catalog().label(id))
+                    .collect();
+
+                if !missing.is_empty() {
+                    return Err(anyhow!(
+                        "Offers not found on the offers page of {}: {}",
+                        settings.user_name(),
+                        missing.join(", ")
+                    ));
+                }
+
+                let mut backups = Vec::new();
+
+                for offer in offers_page {
+                    if !offers.is_empty() && !selected.contains(&offer.id) {
+                        continue;
+                    }
+
+                    info!("Exporting offer: {}", offer.id);
+                    backups.push(OfferBackup::new(
+                        offer.id,
+                        offer.title,
+                        offer.active,
+                        session.offer_details(offer.id).await?,
+                    ));
+                }
+
+                Ok::<_, anyhow::Error>(backups)
+            })
+            .await?;
+
+        accounts.push(AccountBackup {
+            user_name: settings.user_name().to_string(),
+            offers: backups,
+        });
+    }
+
+    Backup::new(accounts)
+        .write_to_file_pretty(output)
+        .map_err(|error| anyhow!("{error}"))
+}
@@ -0,0 +1,123 @@
+use std::collections::BTreeSet;
+
+use anyhow::anyhow;
+use log::info;
+use serde_rw::FromFile;
+
+use super::{confirm, filter_accounts};
+use crate::args::ImportArgs;
+use crate::backup::{Backup, OfferBackup, VERSION};
+use crate::client::Session;
+use crate::diff::diff;
+use crate::settings::Settings;
+
+/// Restore offers from a backup file.
+///
+/// Existing offers are updated to match the backup and missing offers are recreated.
+/// All changes are previewed and require confirmation unless `--yes` is given.
+/// Unless `--account` is given, all accounts of the backup must be configured.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the backup could not be read, contains unconfigured accounts,
+/// a login failed or the offers could not be updated.
+pub async fn run(args: ImportArgs) -> anyhow::Result<()> {
+    let backup = Backup::from_file(&args.backup).map_err(|error| anyhow!("{error}"))?;
+
+    if backup.version > VERSION {
+        return Err(anyhow!("Unsupported backup version: {}", backup.version));
+    }
+
+    let settings = filter_accounts(Settings::load(&args.config_file)?, args.account.as_deref())?;
+
+    if args.account.is_none()
+        && let Some(account) = backup.accounts.iter().find(|account| {
+            settings
+                .iter()
+                .all(|settings| settings.user_name() != account.user_name)
+        })
+    {
+        return Err(anyhow!(
+            "Account {} of the backup is not configured in {}, please specify --account to restore the others",
+            account.user_name,
+            args.config_file.display()
+        ));
+    }
+
+    for account in &backup.accounts {
+        let Some(settings) = settings
+            .iter()
+            .find(|settings| settings.user_name() == account.user_name)
+        else {
+            continue;
+        };
+        settings
+            .with_session(async |session| {
+                let existing: BTreeSet<u32> = session
+                    .offers()
+                    .await?
+                    .into_iter()
+                    .map(|offer| offer.id)
+                    .collect();
+
+                for offer in &account.offers {
+                    if existing.contains(&offer.id) {
+                        update(session, offer, &args).await?;
+                    } else {
+                        recreate(session, offer, &args).await?;
+                    }
+                }
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn update(session: &Session, offer: &OfferBackup, args: &ImportArgs) -> anyhow::Result<()> {
+    let changes = diff(
+        &session.offer_details(offer.id).await?,
+        offer.restorable_fields(),
+    );
+
+    if changes.is_empty() {
+        println!("Offer {} is up to date.", offer.id);
+        return Ok(());
+    }
+
+    println!("Changes to offer {}:", offer.id);
+    for change in &changes {
+        print!("{change}");
+    }
+
+    if args.dry_run || !(args.yes || confirm("Apply these changes?")?) {
+        return Ok(());
+    }
+
+    info!("Restoring offer: {}", offer.id);
+    session.edit(offer.id, &offer.restorable_details()).await?;
+    Ok(())
+}
+
+async fn recreate(session: &Session, offer: &OfferBackup, args: &ImportArgs) -> anyhow::Result<()> {
+    println!(
+        "Offer {} ({}) does not exist anymore.",
+        offer.id,
+        offer.title.as_deref().unwrap_or("untitled")
+    );
+
+    if args.dry_run || !(args.yes || confirm("Recreate it?")?) {
+        return Ok(());
+    }
+
+    let id = session.create(&offer.restorable_details()).await?;
+    info!("Recreated offer {} as {id}", offer.id);
+
+    if !offer.active {
+        session.deactivate(id).await?;
+    }
+
+    Ok(())
+}
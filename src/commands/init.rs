@@ -0,0 +1,337 @@
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::Write as _;
+use std::path::Path;
+
+use anyhow::anyhow;
+use log::warn;
+use toml_edit::Value;
+
+use super::{confirm, prompt};
+use crate::client::Client;
+use crate::credentials::{Credentials, Verification};
+use crate::offer::Offer;
+
+const DEFAULT_MAX_AGE_MIN: u64 = 360;
+
+/// Interactively create a config file.
+///
+/// Asks for the credentials of one or more accounts, verifies them with a test login and asks
+/// what shall happen to each of the discovered offers.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if reading the answers, logging in or writing the file failed.
+pub async fn run(config_file: &Path) -> anyhow::Result<()> {
+    if !config_file
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("toml"))
+    {
+        return Err(anyhow!(
+            "Can only create TOML config files, but {} does not end with .toml",
+            config_file.display()
+        ));
+    }
+
+    if config_file.exists()
+        && !confirm(&format!(
+            "{} already exists. Overwrite it?",
+            config_file.display()
+        ))?
+    {
+        return Ok(());
+    }
+
+    let mut accounts = Vec::new();
+
+    loop {
+        if let Some(account) = ask_account().await? {
+            accounts.push(account);
+        }
+
+        if !confirm("Add another account?")? {
+            break;
+        }
+    }
+
+    if accounts.is_empty() {
+        return Err(anyhow!("No accounts to write"));
+    }
+
+    create(config_file)?.write_all(render(&accounts).as_bytes())?;
+    println!("Wrote {}", config_file.display());
+    Ok(())
+}
+
+#[derive(Debug)]
+struct NewAccount {
+    user_name: String,
+    password: String,
+    offers: Vec<(Offer, Choice)>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Choice {
+    Activate,
+    Bump,
+    Deactivate,
+    Fresh { max_age_min: u64 },
+    Skip,
+}
+
+async fn ask_account() -> anyhow::Result<Option<NewAccount>> {
+    loop {
+        let user_name = prompt("User name: ")?;
+        let password = rpassword::prompt_password("Password: ")?;
+        println!("Logging in as {user_name}...");
+
+        let offers = match Client::default()
+            .login(Credentials::new(
+                user_name.clone(),
+                password.clone(),
+                false,
+                Verification::Prompt,
+            ))
+            .await
+        {
+            Ok(session) => {
+                let offers = session.offers().await;
+
+                if let Err(error) = session.logout().await {
+                    warn!("Could not log out: {error}");
+                }
+
+                offers.map_err(|error| anyhow!("Could not list offers: {error}"))
+            }
+            Err(error) => Err(anyhow!("Login failed: {error}")),
+        };
+        let offers = match offers {
+            Ok(offers) => offers,
+            Err(error) => {
+                println!("{error}");
+
+                if confirm("Try again?")? {
+                    continue;
+                }
+
+                return Ok(None);
+            }
+        };
+
+        println!("Found {} offer(s).", offers.len());
+        let mut choices = Vec::with_capacity(offers.len());
+
+        for offer in offers {
+            println!("{offer}");
+            let choice = ask_choice()?;
+            choices.push((offer, choice));
+        }
+
+        return Ok(Some(NewAccount {
+            user_name,
+            password,
+            offers: choices,
+        }));
+    }
+}
+
+fn ask_choice() -> anyhow::Result<Choice> {
+    loop {
+        let choice =
+            match prompt("  [b]ump, [a]ctivate, [d]eactivate, keep [f]resh or [s]kip (default)? ")?
+                .to_lowercase()
+                .as_str()
+            {
+                "b" | "bump" => Choice::Bump,
+                "a" | "activate" => Choice::Activate,
+                "d" | "deactivate" => Choice::Deactivate,
+                "f" | "fresh" => Choice::Fresh {
+                    max_age_min: ask_max_age_min()?,
+                },
+                "" | "s" | "skip" => Choice::Skip,
+                other => {
+                    println!("  Unknown choice: {other}");
+                    continue;
+                }
+            };
+        return Ok(choice);
+    }
+}
+
+fn ask_max_age_min() -> anyhow::Result<u64> {
+    loop {
+        let answer = prompt(&format!(
+            "  Bump at least every how many minutes [{DEFAULT_MAX_AGE_MIN}]? "
+        ))?;
+
+        if answer.is_empty() {
+            return Ok(DEFAULT_MAX_AGE_MIN);
+        }
+
+        match answer.parse() {
+            Ok(max_age_min) => return Ok(max_age_min),
+            Err(error) => println!("  Invalid number of minutes: {error}"),
+        }
+    }
+}
+
+fn render(accounts: &[NewAccount]) -> String {
+    let mut config = String::from(
+        "# Configuration of wg_gesucht_updater.\n\
+         # See the README for further options, e.g. webhooks, email alerts and offer texts.\n",
+    );
+
+    for account in accounts {
+        let _ = write!(
+            config,
+            "\n[[accounts]]\n\
+             user_name = {}\n\
+             password = {}\n\
+             # timeout_sec = 10\n\
+             # user_agent = \"...\"\n\
+             # autologin = true\n\
+             # totp_secret = \"...\"\n",
+            Value::from(account.user_name.as_str()),
+            Value::from(account.password.as_str()),
+        );
+
+        for (key, choice) in [
+            ("bump", Choice::Bump),
+            ("activate", Choice::Activate),
+            ("deactivate", Choice::Deactivate),
+        ] {
+            let offers: Vec<&Offer> = account
+                .offers
+                .iter()
+                .filter(|(_, other)| *other == choice)
+                .map(|(offer, _)| offer)
+                .collect();
+
+            if !offers.is_empty() {
+                let _ = writeln!(config, "{key} = [");
+
+                for offer in offers {
+                    let _ = writeln!(config, "    {},{}", offer.id, comment(offer));
+                }
+
+                config.push_str("]\n");
+            }
+        }
+
+        let fresh: Vec<(&Offer, u64)> = account
+            .offers
+            .iter()
+            .filter_map(|(offer, choice)| match choice {
+                Choice::Fresh { max_age_min } => Some((offer, *max_age_min)),
+                _ => None,
+            })
+            .collect();
+
+        if !fresh.is_empty() {
+            config.push_str("desired = [\n");
+
+            for (offer, max_age_min) in fresh {
+                let _ = writeln!(
+                    config,
+                    "    {{ id = {}, state = \"fresh\", max_age_min = {max_age_min} }},{}",
+                    offer.id,
+                    comment(offer)
+                );
+            }
+
+            config.push_str("]\n");
+        }
+    }
+
+    config
+}
+
+/// Return a trailing comment with the title of the offer, if any.
+fn comment(offer: &Offer) -> String {
+    offer
+        .title
+        .as_ref()
+        .map(|title| format!("  # {}", title.replace(['\r', '\n'], " ")))
+        .unwrap_or_default()
+}
+
+/// Create or truncate the config file, making it readable by its owner only since it contains passwords.
+#[cfg(unix)]
+fn create(config_file: &Path) -> std::io::Result<File> {
+    use std::fs::{Permissions, set_permissions};
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(config_file)?;
+    set_permissions(config_file, Permissions::from_mode(0o600))?;
+    Ok(file)
+}
+
+/// Create or truncate the config file.
+#[cfg(not(unix))]
+fn create(config_file: &Path) -> std::io::Result<File> {
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(config_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{remove_file, write};
+
+    use super::{Choice, NewAccount, render};
+    use crate::config_file::{ConfigFile, DesiredState};
+    use crate::offer::Offer;
+
+    fn offer(id: u32, title: &str) -> Offer {
+        Offer {
+            id,
+            title: Some(title.to_string()),
+            active: true,
+            last_bumped: None,
+        }
+    }
+
+    #[test]
+    fn render_parses() {
+        let config = render(&[NewAccount {
+            user_name: "user@example.com".to_string(),
+            password: "se\"cret # not a comment".to_string(),
+            offers: vec![
+                (offer(1, "Zimmer in\nAltona"), Choice::Bump),
+                (offer(2, "WG # 2"), Choice::Activate),
+                (offer(3, "Altbau"), Choice::Deactivate),
+                (offer(4, "Neubau"), Choice::Fresh { max_age_min: 120 }),
+                (offer(5, "Keller"), Choice::Skip),
+            ],
+        }]);
+        let path = std::env::temp_dir().join(format!(
+            "wg_gesucht_updater-init-{}.toml",
+            std::process::id()
+        ));
+        write(&path, config).expect("Config file should be written.");
+        let config_file = ConfigFile::load(&path);
+        remove_file(&path).expect("Config file should be removed.");
+
+        let config_file = config_file.expect("Rendered config should be valid.");
+        let [account] = config_file.accounts.as_slice() else {
+            panic!("Expected one account");
+        };
+        assert_eq!(account.user_name, "user@example.com");
+        assert_eq!(account.password, "se\"cret # not a comment");
+        assert_eq!(account.ids(&account.bump), [1]);
+        assert_eq!(account.ids(&account.activate), [2]);
+        assert_eq!(account.ids(&account.deactivate), [3]);
+
+        let desired = account.desired();
+        assert_eq!(desired.len(), 1);
+        assert_eq!(desired[0].id, 4);
+        assert_eq!(desired[0].state, DesiredState::Fresh { max_age_min: 120 });
+    }
+}
@@ -0,0 +1,56 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::filter_accounts;
+use crate::conversation::Conversation;
+use crate::settings::Settings;
+
+/// Print a digest of the conversations of the configured accounts, grouped by offer.
+///
+/// Unread conversations are marked with an asterisk.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the config file could not be read,
+/// a login failed or the conversations could not be fetched.
+pub async fn run(config_file: &Path, account: Option<&str>, unread: bool) -> anyhow::Result<()> {
+    for settings in filter_accounts(Settings::load(config_file)?, account)? {
+        let mut offers: BTreeMap<Option<u32>, Vec<Conversation>> = BTreeMap::new();
+
+        for conversation in settings
+            .with_session(async |session| session.list_conversations().await)
+            .await?
+        {
+            if conversation.unread || !unread {
+                offers
+                    .entry(conversation.offer)
+                    .or_default()
+                    .push(conversation);
+            }
+        }
+
+        println!("{}:", settings.user_name());
+
+        if offers.is_empty() {
+            println!("  No conversations.");
+        }
+
+        for (offer, mut conversations) in offers {
+            conversations.sort_by_key(|conversation| Reverse(conversation.timestamp));
+
+            match offer {
+                Some(offer) => print!("  Offer #{offer}"),
+                None => print!("  Without offer"),
+            }
+
+            println!(" ({} conversations):", conversations.len());
+
+            for conversation in conversations {
+                println!("    {conversation}");
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,213 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::read_dir;
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use super::confirm;
+use crate::client::Session;
+use crate::config_file::{Account, ConfigFile, PhotoDirectory};
+use crate::image::LocalImage;
+use crate::settings::Settings;
+use crate::state::{PersistentState, state_dir};
+
+const EXTENSIONS: [&str; 3] = ["jpg", "jpeg", "png"];
+
+/// An image that has been uploaded from a local file.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct SyncedPhoto {
+    image_id: String,
+    fingerprint: String,
+}
+
+/// Uploaded images per offer and file name.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct PhotoState(BTreeMap<u32, BTreeMap<String, SyncedPhoto>>);
+
+impl PersistentState for PhotoState {
+    const FILE_NAME: &'static str = "photos.json";
+}
+
+/// Mirror the configured photo directories to the respective offers' galleries.
+///
+/// Images are ordered by their file names, the first one becoming the title image.
+/// Images previously uploaded by this command that have no counterpart in the directory anymore
+/// are deleted. Other images of the gallery are kept after the uploaded ones unless `prune` is set.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the config file or an image could not be read,
+/// a login failed or the gallery could not be updated.
+pub async fn run(
+    config_file: &Path,
+    account: Option<&str>,
+    dry_run: bool,
+    yes: bool,
+    prune: bool,
+) -> anyhow::Result<()> {
+    let config_file = ConfigFile::load(config_file)?;
+    let state_dir = state_dir(config_file.state_dir.clone());
+    let mut state = PhotoState::load(&state_dir);
+    let accounts: Vec<&Account> = config_file
+        .accounts
+        .iter()
+        .filter(|candidate| account.is_none_or(|account| candidate.user_name == account))
+        .collect();
+
+    if let (Some(account), true) = (account, accounts.is_empty()) {
+        return Err(anyhow!("No such account: {account}"));
+    }
+    let options = Options {
+        dry_run,
+        yes,
+        prune,
+    };
+
+    for account in accounts
+        .into_iter()
+        .filter(|account| !account.photos.is_empty())
+    {
+        Settings::from_config(&config_file, account)
+            .with_session(async |session| {
+                for photos in &account.photos {
+                    sync(session, photos, &mut state, &state_dir, options).await?;
+                }
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Options {
+    dry_run: bool,
+    yes: bool,
+    prune: bool,
+}
+
+async fn sync(
+    session: &Session,
+    photos: &PhotoDirectory,
+    state: &mut PhotoState,
+    state_dir: &Path,
+    options: Options,
+) -> anyhow::Result<()> {
+    let images = local_images(&photos.directory)?;
+    let remote: Vec<String> = session
+        .images(photos.offer)
+        .await?
+        .into_iter()
+        .map(|image| image.id)
+        .collect();
+    let synced = state.0.entry(photos.offer).or_default();
+    synced.retain(|_, photo| remote.contains(&photo.image_id));
+    let uploaded: BTreeSet<String> = synced
+        .values()
+        .map(|photo| photo.image_id.clone())
+        .collect();
+    let kept: BTreeMap<String, String> = images
+        .iter()
+        .filter_map(|image| {
+            synced
+                .get(&image.file_name())
+                .filter(|photo| photo.fingerprint == image.fingerprint())
+                .map(|photo| (image.file_name(), photo.image_id.clone()))
+        })
+        .collect();
+    let kept_ids: BTreeSet<&String> = kept.values().collect();
+    let uploads: Vec<&LocalImage> = images
+        .iter()
+        .filter(|image| !kept.contains_key(&image.file_name()))
+        .collect();
+    let (deletions, foreign): (Vec<&String>, Vec<&String>) = remote
+        .iter()
+        .filter(|id| !kept_ids.contains(id))
+        .partition(|id| options.prune || uploaded.contains(*id));
+
+    if uploads.is_empty()
+        && deletions.is_empty()
+        && kept
+            .values()
+            .chain(foreign.iter().copied())
+            .eq(remote.iter())
+    {
+        println!("Gallery of offer {} is up to date.", photos.offer);
+        return Ok(());
+    }
+
+    println!("Changes to the gallery of offer {}:", photos.offer);
+    for image in &uploads {
+        println!("+ upload {}", image.file_name());
+    }
+    for id in &deletions {
+        println!("- delete image {id}");
+    }
+    for id in &foreign {
+        println!("  keep image {id}, which was not uploaded from the directory");
+    }
+
+    if options.dry_run || !(options.yes || confirm("Apply these changes?")?) {
+        return Ok(());
+    }
+
+    let mut order = Vec::with_capacity(images.len() + foreign.len());
+
+    for image in &images {
+        let image_id = if let Some(image_id) = kept.get(&image.file_name()) {
+            image_id.clone()
+        } else {
+            info!(
+                "Uploading {} to offer {}",
+                image.path.display(),
+                photos.offer
+            );
+            let image_id = session.upload_image(photos.offer, image).await?.id;
+            state.0.entry(photos.offer).or_default().insert(
+                image.file_name(),
+                SyncedPhoto {
+                    image_id: image_id.clone(),
+                    fingerprint: image.fingerprint(),
+                },
+            );
+            state.save(state_dir)?;
+            image_id
+        };
+        order.push(image_id);
+    }
+
+    for id in deletions {
+        info!("Deleting image {id} of offer {}", photos.offer);
+        session.delete_image(photos.offer, id).await?;
+        state
+            .0
+            .entry(photos.offer)
+            .or_default()
+            .retain(|_, photo| photo.image_id != *id);
+        state.save(state_dir)?;
+    }
+
+    order.extend(foreign.into_iter().cloned());
+    info!("Arranging images of offer {}", photos.offer);
+    session.arrange_images(photos.offer, &order).await?;
+    Ok(())
+}
+
+fn local_images(directory: &Path) -> anyhow::Result<Vec<LocalImage>> {
+    let mut paths: Vec<PathBuf> = read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    paths.retain(|path| {
+        path.is_file()
+            && path.extension().is_some_and(|extension| {
+                EXTENSIONS.contains(&extension.to_string_lossy().to_lowercase().as_str())
+            })
+    });
+    paths.sort();
+    paths.iter().map(|path| LocalImage::read(path)).collect()
+}
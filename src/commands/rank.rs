@@ -0,0 +1,111 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use log::{info, warn};
+
+use super::filter_accounts;
+use crate::config_file::{ConfigFile, Ranking};
+use crate::error::{Error, FailedUpdates};
+use crate::reconcile::Operation;
+
+const DEFAULT_PAGES: u32 = 3;
+
+/// Print the positions of the configured offers in the public search.
+///
+/// If `bump_below` is configured, active offers ranking below that position
+/// or not within the searched pages at all are bumped, unless `dry_run` is set.
+/// Accounts whose search yields no results at all are skipped with a warning.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the config file could not be read,
+/// the search failed or offers could not be bumped.
+pub async fn run(config_file: &Path, account: Option<&str>, dry_run: bool) -> anyhow::Result<()> {
+    let config_file = ConfigFile::load(config_file)?;
+
+    let rankings: BTreeMap<String, Ranking> = config_file
+        .accounts
+        .iter()
+        .filter_map(|account| {
+            account
+                .ranking
+                .clone()
+                .map(|ranking| (account.user_name.clone(), ranking))
+        })
+        .collect();
+
+    for settings in filter_accounts(config_file.into(), account)? {
+        let Some(ranking) = rankings.get(settings.user_name()) else {
+            continue;
+        };
+        let listings = settings
+            .client()
+            .search_pages(&ranking.query, ranking.pages.unwrap_or(DEFAULT_PAGES))
+            .await?;
+
+        if listings.is_empty() {
+            warn!(
+                "No search results for {}, not ranking or bumping any offers",
+                settings.user_name()
+            );
+            continue;
+        }
+
+        println!("{}:", settings.user_name());
+        let mut too_low = Vec::new();
+
+        for id in settings.offers() {
+            let position = listings
+                .iter()
+                .position(|listing| listing.id == id)
+                .map(|index| index + 1);
+
+            match position {
+                Some(position) => println!("  #{id}: position {position}"),
+                None => println!("  #{id}: not within the first {} results", listings.len()),
+            }
+
+            if ranking
+                .bump_below
+                .is_some_and(|limit| position.is_none_or(|position| position > limit))
+            {
+                too_low.push(id);
+            }
+        }
+
+        if too_low.is_empty() || dry_run {
+            continue;
+        }
+
+        settings
+            .with_session(async |session| {
+                let active: BTreeSet<u32> = session
+                    .offers()
+                    .await?
+                    .into_iter()
+                    .filter(|offer| offer.active)
+                    .map(|offer| offer.id)
+                    .collect();
+                let mut failed_updates = FailedUpdates::default();
+
+                for id in too_low {
+                    if active.contains(&id) {
+                        settings
+                            .perform(session, id, Operation::Bump, &mut failed_updates)
+                            .await;
+                    } else {
+                        info!("Not bumping inactive offer: {id}");
+                    }
+                }
+
+                if failed_updates.is_empty() {
+                    Ok(())
+                } else {
+                    Err(anyhow::Error::from(Error::from(failed_updates)))
+                }
+            })
+            .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,210 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use super::filter_accounts;
+use crate::config_file::ConfigFile;
+use crate::offer_stats::OfferStats;
+use crate::state::{PersistentState, state_dir};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Counts of an offer at a point in time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Snapshot {
+    timestamp: DateTime<Utc>,
+    views: Option<u64>,
+    contacts: Option<u64>,
+}
+
+/// Recorded snapshots per account and offer.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct StatsHistory(BTreeMap<String, BTreeMap<u32, Vec<Snapshot>>>);
+
+impl PersistentState for StatsHistory {
+    const FILE_NAME: &'static str = "stats_history.json";
+}
+
+/// Print the view and contact counts of the offers of the configured accounts.
+///
+/// If `record` is set, the counts are stored as a snapshot in the state directory.
+/// If `history` is set, the recorded snapshots are printed instead, along with the
+/// number of views and contacts per day between consecutive snapshots.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the config file could not be read, a login failed,
+/// the offers could not be read or the history could not be saved.
+pub async fn run(
+    config_file: &Path,
+    account: Option<&str>,
+    record: bool,
+    history: bool,
+) -> anyhow::Result<()> {
+    let config_file = ConfigFile::load(config_file)?;
+    let state_dir = state_dir(config_file.state_dir.clone());
+    let mut stats_history = StatsHistory::load(&state_dir);
+    let accounts = filter_accounts(config_file.into(), account)?;
+
+    if history {
+        for settings in accounts {
+            print_history(
+                settings.user_name(),
+                stats_history.0.get(settings.user_name()),
+            );
+        }
+
+        return Ok(());
+    }
+
+    let timestamp = Utc::now();
+
+    for settings in accounts {
+        let stats = settings
+            .with_session(async |session| session.offer_stats().await)
+            .await?;
+        println!("{}:", settings.user_name());
+
+        if !stats.is_empty()
+            && stats
+                .iter()
+                .all(|offer| offer.views.is_none() && offer.contacts.is_none())
+        {
+            warn!(
+                "No view or contact counts found for {}, the offers page layout may have changed",
+                settings.user_name()
+            );
+        }
+
+        for offer in &stats {
+            println!("  {offer}");
+        }
+
+        if record {
+            let offers = stats_history
+                .0
+                .entry(settings.user_name().to_string())
+                .or_default();
+
+            for OfferStats {
+                id,
+                views,
+                contacts,
+            } in stats
+            {
+                offers.entry(id).or_default().push(Snapshot {
+                    timestamp,
+                    views,
+                    contacts,
+                });
+            }
+        }
+    }
+
+    if record {
+        stats_history.save(&state_dir)?;
+    }
+
+    Ok(())
+}
+
+fn print_history(account: &str, offers: Option<&BTreeMap<u32, Vec<Snapshot>>>) {
+    println!("{account}:");
+
+    let Some(offers) = offers.filter(|offers| !offers.is_empty()) else {
+        println!("  No snapshots recorded.");
+        return;
+    };
+
+    for (id, snapshots) in offers {
+        println!("  #{id}:");
+        let mut previous: Option<&Snapshot> = None;
+
+        for snapshot in snapshots {
+            println!(
+                "    {}{}{}",
+                snapshot.timestamp.format("%Y-%m-%d %H:%M"),
+                format_count(
+                    "views",
+                    snapshot.views,
+                    previous.map(|previous| (previous.views, previous.timestamp)),
+                    snapshot.timestamp,
+                ),
+                format_count(
+                    "contacts",
+                    snapshot.contacts,
+                    previous.map(|previous| (previous.contacts, previous.timestamp)),
+                    snapshot.timestamp,
+                )
+            );
+            previous = Some(snapshot);
+        }
+    }
+}
+
+/// Format a count along with its change since the previous snapshot and the change per day.
+fn format_count(
+    name: &str,
+    count: Option<u64>,
+    previous: Option<(Option<u64>, DateTime<Utc>)>,
+    timestamp: DateTime<Utc>,
+) -> String {
+    let Some(count) = count else {
+        return format!("  {name} unknown");
+    };
+
+    match previous {
+        Some((Some(previous), previous_timestamp)) => {
+            let delta = i64::try_from(count)
+                .unwrap_or(i64::MAX)
+                .saturating_sub(i64::try_from(previous).unwrap_or(i64::MAX));
+            let seconds = (timestamp - previous_timestamp).num_seconds().max(1);
+            format!(
+                "  {count} {name} ({delta:+}, {}/day)",
+                delta.saturating_mul(SECONDS_PER_DAY) / seconds
+            )
+        }
+        _ => format!("  {count} {name}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, TimeDelta, Utc};
+
+    use super::format_count;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).expect("valid timestamp")
+    }
+
+    #[test]
+    fn count() {
+        let two_days_ago = now() - TimeDelta::days(2);
+        assert_eq!(
+            format_count("views", Some(130), Some((Some(100), two_days_ago)), now()),
+            "  130 views (+30, 15/day)"
+        );
+        assert_eq!(
+            format_count("views", Some(90), Some((Some(100), two_days_ago)), now()),
+            "  90 views (-10, -5/day)"
+        );
+        assert_eq!(
+            format_count("contacts", Some(3), Some((Some(1), now())), now()),
+            "  3 contacts (+2, 172800/day)"
+        );
+        assert_eq!(
+            format_count("views", Some(130), Some((None, two_days_ago)), now()),
+            "  130 views"
+        );
+        assert_eq!(format_count("views", Some(130), None, now()), "  130 views");
+        assert_eq!(
+            format_count("views", None, Some((Some(100), two_days_ago)), now()),
+            "  views unknown"
+        );
+    }
+}
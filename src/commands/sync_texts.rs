@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use log::info;
+
+use super::confirm;
+use crate::config_file::ConfigFile;
+use crate::settings::Settings;
+
+/// Render the templated description texts and push them to the respective offers.
+///
+/// Offers whose texts already match the rendered templates are skipped.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the config file could not be read, a template could
+/// not be rendered, a login failed or the offers could not be read or updated.
+pub async fn run(
+    config_file: &Path,
+    account: Option<&str>,
+    dry_run: bool,
+    yes: bool,
+) -> anyhow::Result<()> {
+    let config_file = ConfigFile::load(config_file)?;
+
+    for account in config_file
+        .accounts
+        .iter()
+        .filter(|candidate| account.is_none_or(|account| candidate.user_name == account))
+    {
+        let updates = account
+            .texts
+            .iter()
+            .map(|texts| {
+                texts
+                    .render(&config_file.templates)
+                    .map(|update| (texts.offer, update))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        if updates.is_empty() {
+            continue;
+        }
+
+        Settings::from_config(&config_file, account)
+            .with_session(async |session| {
+                for (id, update) in updates {
+                    let changes = update.diff(&session.offer_details(id).await?);
+
+                    if changes.is_empty() {
+                        println!("Texts of offer {id} are up to date.");
+                        continue;
+                    }
+
+                    println!("Changes to offer {id}:");
+                    for change in &changes {
+                        print!("{change}");
+                    }
+
+                    if dry_run || !(yes || confirm("Apply these changes?")?) {
+                        continue;
+                    }
+
+                    info!("Syncing texts of offer: {id}");
+                    session.edit(id, &update).await?;
+                }
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,402 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use log::{error, info};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Row, Table, TableState, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use tokio::task::spawn_blocking;
+
+use crate::client::Session;
+use crate::config_file::{Actions, ConfigFile, write_actions};
+use crate::error::FailedUpdates;
+use crate::offer::Offer;
+use crate::reconcile::Operation;
+use crate::settings::Settings;
+
+const MAX_LOG_LINES: usize = 500;
+const HELP: &str = "↑/↓ select  a/d/b activate/deactivate/bump  A/D/B toggle in config  w write config  r reload  q quit";
+
+static LOG: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Log target that keeps the log lines for the side panel instead of writing them to the terminal.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogBuffer;
+
+impl Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut log = LOG.lock().unwrap_or_else(PoisonError::into_inner);
+        log.extend(
+            String::from_utf8_lossy(buf)
+                .lines()
+                .map(ToString::to_string),
+        );
+        let excess = log.len().saturating_sub(MAX_LOG_LINES);
+        log.drain(..excess);
+        drop(log);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Manage the offers of all accounts of a config file in an interactive terminal UI.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the config file could not be read or the terminal could not be used.
+pub async fn run(config_file: &Path) -> anyhow::Result<()> {
+    let mut app = App::load(config_file).await?;
+    let result = match ratatui::try_init() {
+        Ok(mut terminal) => {
+            let result = app.run(&mut terminal).await;
+            ratatui::restore();
+            result
+        }
+        Err(error) => Err(error.into()),
+    };
+    app.logout().await;
+    result
+}
+
+#[derive(Debug)]
+struct AccountView {
+    settings: Settings,
+    session: Option<Session>,
+    login_error: Option<String>,
+    offers: Vec<Offer>,
+    actions: Actions,
+    failed_updates: FailedUpdates,
+}
+
+impl AccountView {
+    async fn refresh(&mut self) {
+        let Some(session) = &self.session else {
+            return;
+        };
+
+        match session.offers().await {
+            Ok(offers) => self.offers = offers,
+            Err(error) => error!(
+                "Could not list offers of {}: {error}",
+                self.settings.user_name()
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct App {
+    config_file: PathBuf,
+    accounts: Vec<AccountView>,
+    table: TableState,
+    status: Option<String>,
+    unsaved: bool,
+    quitting: bool,
+}
+
+impl App {
+    async fn load(config_file: &Path) -> anyhow::Result<Self> {
+        let config = ConfigFile::load(config_file)?;
+        let mut accounts = Vec::with_capacity(config.accounts.len());
+
+        for account in &config.accounts {
+            let settings = Settings::from_config(&config, account);
+            info!("Logging into account: {}", settings.user_name());
+            let (session, login_error) = match settings.login().await {
+                Ok(session) => (Some(session), None),
+                Err(error) => {
+                    error!("Could not log into {}: {error}", settings.user_name());
+                    (None, Some(error.to_string()))
+                }
+            };
+            let mut view = AccountView {
+                session,
+                login_error,
+                settings,
+                offers: Vec::new(),
+                actions: account.into(),
+                failed_updates: FailedUpdates::default(),
+            };
+            view.refresh().await;
+            accounts.push(view);
+        }
+
+        Ok(Self {
+            config_file: config_file.to_path_buf(),
+            accounts,
+            table: TableState::default().with_selected(Some(0)),
+            status: None,
+            unsaved: false,
+            quitting: false,
+        })
+    }
+
+    /// Log out of all accounts, so that the sessions opened by [`Self::load`] do not linger.
+    async fn logout(self) {
+        for account in self.accounts {
+            if let Some(session) = account.session {
+                account.settings.logout(session).await;
+            }
+        }
+    }
+
+    async fn run(&mut self, terminal: &mut DefaultTerminal) -> anyhow::Result<()> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+
+            // Reading blocks until the next event, so it must not stall the runtime's workers.
+            let Event::Key(key) = spawn_blocking(event::read).await?? else {
+                continue;
+            };
+
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            let quitting = std::mem::take(&mut self.quitting);
+            self.status = None;
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => {
+                    if !self.unsaved || quitting {
+                        return Ok(());
+                    }
+
+                    self.status =
+                        Some("Unsaved config changes, press q again to quit anyway".to_string());
+                    self.quitting = true;
+                }
+                KeyCode::Up | KeyCode::Char('k') => self.table.select_previous(),
+                KeyCode::Down | KeyCode::Char('j') => self.table.select_next(),
+                KeyCode::Char(key @ ('a' | 'd' | 'b')) => {
+                    self.status = Some("Working...".to_string());
+                    terminal.draw(|frame| self.render(frame))?;
+                    self.perform(operation(key)).await;
+                }
+                KeyCode::Char(key @ ('A' | 'D' | 'B')) => {
+                    self.toggle(operation(key.to_ascii_lowercase()));
+                }
+                KeyCode::Char('w') => self.save(),
+                KeyCode::Char('r') => {
+                    self.status = Some("Reloading...".to_string());
+                    terminal.draw(|frame| self.render(frame))?;
+
+                    for account in &mut self.accounts {
+                        account.refresh().await;
+                    }
+
+                    self.status = None;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn entries(&self) -> impl Iterator<Item = (usize, &Offer)> {
+        self.accounts
+            .iter()
+            .enumerate()
+            .flat_map(|(index, account)| account.offers.iter().map(move |offer| (index, offer)))
+    }
+
+    fn selected(&self) -> Option<(usize, u32)> {
+        self.table
+            .selected()
+            .and_then(|selected| self.entries().nth(selected))
+            .map(|(account, offer)| (account, offer.id))
+    }
+
+    async fn perform(&mut self, operation: Operation) {
+        let Some((index, id)) = self.selected() else {
+            return;
+        };
+        let account = &mut self.accounts[index];
+        let Some(session) = &account.session else {
+            self.status = Some(format!("Not logged into {}", account.settings.user_name()));
+            return;
+        };
+
+        for failures in [
+            &mut account.failed_updates.activate,
+            &mut account.failed_updates.deactivate,
+            &mut account.failed_updates.bump,
+        ] {
+            failures.remove(&id);
+        }
+
+        account
+            .settings
+            .perform(session, id, operation, &mut account.failed_updates)
+            .await;
+        self.status = Some(if account.failed_updates.offers().contains(&id) {
+            format!("Could not {operation} offer #{id}")
+        } else {
+            format!("Performed {operation} on offer #{id}")
+        });
+        account.refresh().await;
+    }
+
+    fn toggle(&mut self, operation: Operation) {
+        let Some((index, id)) = self.selected() else {
+            return;
+        };
+        let actions = &mut self.accounts[index].actions;
+        let operation = (actions.operation(id) != Some(operation)).then_some(operation);
+        actions.set(id, operation);
+        self.unsaved = true;
+    }
+
+    fn save(&mut self) {
+        let actions: Vec<Actions> = self
+            .accounts
+            .iter()
+            .map(|account| account.actions.clone())
+            .collect();
+
+        self.status = Some(match write_actions(&self.config_file, &actions) {
+            Ok(()) => {
+                self.unsaved = false;
+                format!("Wrote {}", self.config_file.display())
+            }
+            Err(error) => format!("Could not write {}: {error}", self.config_file.display()),
+        });
+    }
+
+    fn render(&mut self, frame: &mut Frame<'_>) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [offers, side] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(main);
+        let [failures, log] =
+            Layout::vertical([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(side);
+
+        self.render_offers(frame, offers);
+        render_failures(frame, failures, &self.accounts);
+        render_log(frame, log);
+        frame.render_widget(
+            Paragraph::new(self.status.as_deref().unwrap_or(HELP)).reversed(),
+            footer,
+        );
+    }
+
+    fn render_offers(&mut self, frame: &mut Frame<'_>, area: Rect) {
+        let rows: Vec<Row<'_>> = self
+            .entries()
+            .map(|(index, offer)| {
+                let account = &self.accounts[index];
+                Row::new([
+                    account.settings.user_name().to_string(),
+                    offer.id.to_string(),
+                    account
+                        .settings
+                        .catalog()
+                        .name(offer.id)
+                        .map(ToString::to_string)
+                        .or_else(|| offer.title.clone())
+                        .unwrap_or_default(),
+                    if offer.active { "active" } else { "inactive" }.to_string(),
+                    offer
+                        .last_bumped
+                        .map(|last_bumped| last_bumped.format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_default(),
+                    account
+                        .actions
+                        .operation(offer.id)
+                        .map(|operation| operation.to_string())
+                        .unwrap_or_default(),
+                ])
+            })
+            .collect();
+        let title = if self.unsaved {
+            "Offers (unsaved config changes)"
+        } else {
+            "Offers"
+        };
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(2),
+                Constraint::Length(8),
+                Constraint::Fill(3),
+                Constraint::Length(8),
+                Constraint::Length(16),
+                Constraint::Length(10),
+            ],
+        )
+        .header(Row::new(["Account", "ID", "Name", "State", "Last bump", "Config"]).bold())
+        .block(Block::bordered().title(title))
+        .row_highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(table, area, &mut self.table);
+    }
+}
+
+fn render_failures(frame: &mut Frame<'_>, area: Rect, accounts: &[AccountView]) {
+    let lines: Vec<Line<'_>> = accounts
+        .iter()
+        .flat_map(|account| {
+            let failed_updates = &account.failed_updates;
+            let login = account.login_error.as_ref().map(|error| {
+                Line::from(format!(
+                    "{}: not logged in: {error}",
+                    account.settings.user_name()
+                ))
+            });
+            login.into_iter().chain(
+                [
+                    (Operation::Activate, &failed_updates.activate),
+                    (Operation::Deactivate, &failed_updates.deactivate),
+                    (Operation::Bump, &failed_updates.bump),
+                ]
+                .into_iter()
+                .flat_map(move |(operation, failures)| {
+                    failures.iter().map(move |(id, error)| {
+                        Line::from(format!(
+                            "{}: could not {operation} {}: {error}",
+                            account.settings.user_name(),
+                            account.settings.catalog().label(*id)
+                        ))
+                    })
+                }),
+            )
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(Block::bordered().title("Failures"))
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn render_log(frame: &mut Frame<'_>, area: Rect) {
+    let log = LOG.lock().unwrap_or_else(PoisonError::into_inner);
+    let lines: Vec<Line<'_>> = log
+        .iter()
+        .skip(
+            log.len()
+                .saturating_sub(usize::from(area.height.saturating_sub(2))),
+        )
+        .map(|line| Line::from(line.clone()))
+        .collect();
+    drop(log);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title("Log")),
+        area,
+    );
+}
+
+const fn operation(key: char) -> Operation {
+    match key {
+        'a' => Operation::Activate,
+        'd' => Operation::Deactivate,
+        _ => Operation::Bump,
+    }
+}
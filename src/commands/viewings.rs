@@ -0,0 +1,14 @@
+use std::fs::write;
+use std::path::Path;
+
+use crate::viewing::Viewings;
+
+/// Write the viewings defined in a local file as an iCalendar file.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the viewings could not be read or the calendar could not be written.
+pub fn run(viewings: &Path, output: &Path) -> anyhow::Result<()> {
+    write(output, Viewings::load(viewings)?.calendar().to_string())?;
+    Ok(())
+}
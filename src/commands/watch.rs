@@ -0,0 +1,188 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use chrono::{DateTime, TimeDelta, Utc};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use tokio::time::sleep;
+
+use crate::client::{Client, TIMEOUT, USER_AGENT};
+use crate::config_file::{ConfigFile, Scraping, Watch};
+use crate::listing::Listing;
+use crate::notifier::{Kind, Notification, Notifier};
+use crate::state::{PersistentState, state_dir};
+
+const DEFAULT_PAGES: u32 = 1;
+const RETENTION_DAYS: i64 = 30;
+
+/// IDs of the listings that have already been seen with the time they were last seen, per watch.
+///
+/// Listings that have not been seen for [`RETENTION_DAYS`] are forgotten.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct SeenListings(BTreeMap<String, BTreeMap<u32, DateTime<Utc>>>);
+
+impl SeenListings {
+    /// Record the current listings of a watch and return the ones that have not been seen before.
+    ///
+    /// On the first check of a watch, all listings are recorded and none is returned.
+    fn update(&mut self, watch: &str, listings: Vec<Listing>, now: DateTime<Utc>) -> Vec<Listing> {
+        let first_check = !self.0.contains_key(watch);
+        let seen = self.0.entry(watch.to_string()).or_default();
+        let new = listings
+            .into_iter()
+            .filter(|listing| seen.insert(listing.id, now).is_none() && !first_check)
+            .collect();
+        seen.retain(|_, last_seen| now - *last_seen < TimeDelta::days(RETENTION_DAYS));
+
+        if first_check {
+            info!("Marked {} current listings of {watch} as seen", seen.len());
+        }
+
+        new
+    }
+}
+
+impl PersistentState for SeenListings {
+    const FILE_NAME: &'static str = "seen_listings.json";
+}
+
+/// Watch the configured public searches and send notifications for new listings.
+///
+/// The searches are repeated after `interval` or only checked once if none is given.
+/// On the first check of a watch, all current listings are marked as seen without notification.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the config file could not be read or contains no watches.
+pub async fn run(config_file: &Path, interval: Option<Duration>) -> anyhow::Result<()> {
+    let config_file = ConfigFile::load(config_file)?;
+
+    if config_file.watches.is_empty() {
+        return Err(anyhow!("No watches configured"));
+    }
+
+    let state_dir = state_dir(config_file.state_dir);
+    let notifier = Notifier::new(
+        config_file.webhooks,
+        config_file.smtp,
+        state_dir.clone(),
+        USER_AGENT.to_string(),
+        TIMEOUT,
+    );
+
+    loop {
+        let mut seen = SeenListings::load(&state_dir);
+
+        for watch in &config_file.watches {
+            if let Err(error) = check(watch, &config_file.scraping, &mut seen, &notifier).await {
+                error!("Could not check watch {}: {error}", watch.name);
+            }
+        }
+
+        if let Err(error) = seen.save(&state_dir) {
+            error!("Could not save seen listings: {error}");
+        }
+
+        let Some(interval) = interval else {
+            return Ok(());
+        };
+        sleep(interval).await;
+    }
+}
+
+async fn check(
+    watch: &Watch,
+    scraping: &Scraping,
+    seen: &mut SeenListings,
+    notifier: &Notifier,
+) -> anyhow::Result<()> {
+    let listings = Client::new(
+        watch.timeout_sec.map_or(TIMEOUT, Duration::from_secs),
+        watch
+            .user_agent
+            .clone()
+            .map_or(Cow::Borrowed(USER_AGENT), Cow::Owned),
+        scraping.clone(),
+    )
+    .search_pages(&watch.query, watch.pages.unwrap_or(DEFAULT_PAGES))
+    .await?;
+
+    for listing in seen.update(&watch.name, listings, Utc::now()) {
+        info!("New listing for {}: {listing}", watch.name);
+        notifier
+            .notify(&Notification::new(
+                Kind::NewListing,
+                &watch.name,
+                listing.to_string(),
+                vec![listing.id],
+            ))
+            .await;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, TimeDelta, Utc};
+
+    use super::{RETENTION_DAYS, SeenListings};
+    use crate::listing::Listing;
+
+    fn listings(ids: &[u32]) -> Vec<Listing> {
+        ids.iter()
+            .map(|&id| Listing {
+                id,
+                title: None,
+                rent: None,
+                size: None,
+                url: None,
+            })
+            .collect()
+    }
+
+    fn ids(listings: &[Listing]) -> Vec<u32> {
+        listings.iter().map(|listing| listing.id).collect()
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).expect("valid timestamp")
+    }
+
+    #[test]
+    fn first_check_only_records_listings() {
+        let mut seen = SeenListings::default();
+        assert!(seen.update("kiez", listings(&[1, 2]), now()).is_empty());
+        assert_eq!(seen.0["kiez"].keys().copied().collect::<Vec<_>>(), [1, 2]);
+    }
+
+    #[test]
+    fn first_check_without_listings_is_recorded() {
+        let mut seen = SeenListings::default();
+        assert!(seen.update("kiez", Vec::new(), now()).is_empty());
+        assert_eq!(ids(&seen.update("kiez", listings(&[1]), now())), [1]);
+    }
+
+    #[test]
+    fn new_listings_are_returned_once() {
+        let mut seen = SeenListings::default();
+        seen.update("kiez", listings(&[1, 2]), now());
+        assert_eq!(ids(&seen.update("kiez", listings(&[2, 3]), now())), [3]);
+        assert!(seen.update("kiez", listings(&[1, 3]), now()).is_empty());
+        assert!(seen.update("other", listings(&[1, 3]), now()).is_empty());
+    }
+
+    #[test]
+    fn listings_not_seen_for_the_retention_period_are_forgotten() {
+        let mut seen = SeenListings::default();
+        seen.update("kiez", listings(&[1, 2]), now());
+        let later = now() + TimeDelta::days(RETENTION_DAYS);
+        assert!(seen.update("kiez", listings(&[2]), later).is_empty());
+        assert_eq!(seen.0["kiez"].keys().copied().collect::<Vec<_>>(), [2]);
+        assert_eq!(ids(&seen.update("kiez", listings(&[1]), later)), [1]);
+    }
+}
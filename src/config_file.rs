@@ -1,10 +1,69 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
 pub use account::Account;
+pub use actions::{Actions, write_actions};
+use anyhow::anyhow;
+pub use auto_reply::AutoReply;
+pub use desired_offer::{DesiredOffer, DesiredOffers, DesiredState};
+pub use named_offer::NamedOffer;
+pub use offer_texts::OfferTexts;
+pub use photo_directory::PhotoDirectory;
+pub use ranking::Ranking;
+pub use scraping::{Scraping, Strategy, default_verification_fields};
+pub use search_query::SearchQuery;
 use serde::Deserialize;
+use serde_rw::FromFile;
+pub use watch::Watch;
+
+use crate::notifier::{Smtp, Webhook};
 
 mod account;
+mod actions;
+mod auto_reply;
+mod desired_offer;
+mod named_offer;
+mod offer_texts;
+mod photo_directory;
+mod ranking;
+mod scraping;
+mod search_query;
+mod watch;
 
 /// Configuration file content.
 #[derive(Debug, Eq, Hash, PartialEq, Deserialize)]
 pub struct ConfigFile {
     pub(crate) accounts: Vec<Account>,
+    #[serde(default)]
+    pub(crate) webhooks: Vec<Webhook>,
+    pub(crate) smtp: Option<Smtp>,
+    pub(crate) state_dir: Option<PathBuf>,
+    pub(crate) metrics_file: Option<PathBuf>,
+    pub(crate) api_token: Option<String>,
+    #[serde(default)]
+    pub(crate) templates: BTreeMap<String, String>,
+    #[serde(default)]
+    pub(crate) watches: Vec<Watch>,
+    #[serde(default)]
+    pub(crate) scraping: Scraping,
+}
+
+impl ConfigFile {
+    /// Load the configuration from a file.
+    ///
+    /// Offers referenced by name must be defined in the respective account.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] if the file could not be read or parsed
+    /// or if it references undefined offers.
+    pub fn load(config_file: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let config_file = Self::from_file(config_file).map_err(|error| anyhow!("{error}"))?;
+
+        for account in &config_file.accounts {
+            account.validate()?;
+        }
+
+        Ok(config_file)
+    }
 }
@@ -1,16 +1,104 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::anyhow;
 use serde::Deserialize;
 
+use super::{
+    AutoReply, DesiredOffer, DesiredOffers, NamedOffer, OfferTexts, PhotoDirectory, Ranking,
+};
+use crate::catalog::Catalog;
+use crate::offer_ref::OfferRef;
+
 /// Per-account settings.
-#[derive(Debug, Eq, Hash, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
 pub struct Account {
     pub(crate) user_name: String,
     pub(crate) password: String,
     pub(crate) user_agent: Option<String>,
     pub(crate) timeout_sec: Option<u64>,
+    pub(crate) autologin: Option<bool>,
+    pub(crate) totp_secret: Option<String>,
+    #[serde(default)]
+    pub(crate) offers: Vec<NamedOffer>,
+    #[serde(default)]
+    pub(crate) groups: BTreeMap<String, Vec<OfferRef>>,
+    #[serde(default)]
+    pub(crate) activate: Vec<OfferRef>,
+    #[serde(default)]
+    pub(crate) bump: Vec<OfferRef>,
+    #[serde(default)]
+    pub(crate) deactivate: Vec<OfferRef>,
+    #[serde(default)]
+    pub(crate) desired: Vec<DesiredOffers>,
     #[serde(default)]
-    pub(crate) activate: Vec<u32>,
+    pub(crate) alert_recipients: Vec<String>,
     #[serde(default)]
-    pub(crate) bump: Vec<u32>,
+    pub(crate) texts: Vec<OfferTexts>,
     #[serde(default)]
-    pub(crate) deactivate: Vec<u32>,
+    pub(crate) photos: Vec<PhotoDirectory>,
+    pub(crate) auto_reply: Option<AutoReply>,
+    pub(crate) ranking: Option<Ranking>,
+}
+
+impl Account {
+    /// Return the catalog of the account's named offers and groups.
+    #[must_use]
+    pub fn catalog(&self) -> Catalog {
+        Catalog::new(self.offers.clone(), self.groups.clone())
+    }
+
+    /// Return the IDs of the referenced offers, each only once.
+    ///
+    /// References that cannot be resolved are rejected when loading the config file,
+    /// see [`Self::validate`].
+    #[must_use]
+    pub fn ids(&self, offers: &[OfferRef]) -> Vec<u32> {
+        self.catalog().select(offers).unwrap_or_default()
+    }
+
+    /// Return the desired states of the referenced offers.
+    ///
+    /// If an offer is referenced by multiple entries, the first one applies.
+    #[must_use]
+    pub fn desired(&self) -> Vec<DesiredOffer> {
+        let catalog = self.catalog();
+        let mut seen = BTreeSet::new();
+        self.desired
+            .iter()
+            .flat_map(|desired| {
+                catalog
+                    .resolve(&desired.offers)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|id| DesiredOffer {
+                        id,
+                        state: desired.state,
+                    })
+            })
+            .filter(|desired| seen.insert(desired.id))
+            .collect()
+    }
+
+    /// Check that all offer references can be resolved.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] naming the account if any of the references is invalid.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let catalog = self.catalog();
+        catalog
+            .validate()
+            .and_then(|()| {
+                for offers in [&self.activate, &self.bump, &self.deactivate] {
+                    catalog.select(offers)?;
+                }
+
+                for desired in &self.desired {
+                    catalog.resolve(&desired.offers)?;
+                }
+
+                Ok(())
+            })
+            .map_err(|error| anyhow!("Account {}: {error}", self.user_name))
+    }
 }
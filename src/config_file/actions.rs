@@ -0,0 +1,378 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{read_to_string, write};
+use std::path::Path;
+
+use anyhow::anyhow;
+use serde_json::Value;
+use toml_edit::{Array, DocumentMut, Item};
+
+use super::Account;
+use crate::offer_ref::OfferRef;
+use crate::reconcile::Operation;
+
+/// Keys of the imperative action lists in config files.
+const KEYS: [&str; 3] = ["activate", "bump", "deactivate"];
+
+/// The imperative action lists of an account.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Actions {
+    pub(crate) activate: Vec<u32>,
+    pub(crate) bump: Vec<u32>,
+    pub(crate) deactivate: Vec<u32>,
+    pub(crate) names: BTreeMap<u32, String>,
+    /// The configured references of each list along with the IDs they resolve to.
+    pub(crate) configured: BTreeMap<&'static str, Vec<(OfferRef, Vec<u32>)>>,
+    /// The keys of the lists changed by [`Self::set`].
+    pub(crate) changed: BTreeSet<&'static str>,
+}
+
+impl Actions {
+    /// Return the operation configured for an offer.
+    #[must_use]
+    pub fn operation(&self, id: u32) -> Option<Operation> {
+        [
+            (Operation::Activate, &self.activate),
+            (Operation::Bump, &self.bump),
+            (Operation::Deactivate, &self.deactivate),
+        ]
+        .into_iter()
+        .find_map(|(operation, ids)| ids.contains(&id).then_some(operation))
+    }
+
+    /// Configure the operation of an offer, replacing any previous one.
+    pub fn set(&mut self, id: u32, operation: Option<Operation>) {
+        for (key, candidate, ids) in [
+            (KEYS[0], Operation::Activate, &mut self.activate),
+            (KEYS[1], Operation::Bump, &mut self.bump),
+            (KEYS[2], Operation::Deactivate, &mut self.deactivate),
+        ] {
+            let contained = ids.contains(&id);
+
+            if operation == Some(candidate) {
+                if !contained {
+                    ids.push(id);
+                    self.changed.insert(key);
+                }
+            } else if contained {
+                ids.retain(|&other| other != id);
+                self.changed.insert(key);
+            }
+        }
+    }
+
+    /// Return the changed lists.
+    ///
+    /// Configured references are kept as long as all of their offers are still in the list,
+    /// other offers are referenced by name where possible.
+    fn lists(&self) -> Vec<(&'static str, Vec<OfferRef>)> {
+        [
+            (KEYS[0], &self.activate),
+            (KEYS[1], &self.bump),
+            (KEYS[2], &self.deactivate),
+        ]
+        .into_iter()
+        .filter(|(key, _)| self.changed.contains(key))
+        .map(|(key, ids)| {
+            let mut offers = Vec::new();
+            let mut covered = BTreeSet::new();
+
+            for (offer, resolved) in self.configured.get(key).into_iter().flatten() {
+                if resolved.iter().all(|id| covered.contains(id)) {
+                    continue;
+                }
+
+                if resolved.iter().all(|id| ids.contains(id)) {
+                    offers.push(offer.clone());
+                    covered.extend(resolved);
+                } else {
+                    for &id in resolved {
+                        if ids.contains(&id) && covered.insert(id) {
+                            offers.push(self.reference(id));
+                        }
+                    }
+                }
+            }
+
+            for &id in ids {
+                if covered.insert(id) {
+                    offers.push(self.reference(id));
+                }
+            }
+
+            (key, offers)
+        })
+        .collect()
+    }
+
+    /// Return a reference to the offer by name where possible.
+    fn reference(&self, id: u32) -> OfferRef {
+        self.names
+            .get(&id)
+            .map_or(OfferRef::Id(id), |name| OfferRef::Name(name.clone()))
+    }
+}
+
+impl From<&Account> for Actions {
+    fn from(account: &Account) -> Self {
+        let catalog = account.catalog();
+        Self {
+            activate: account.ids(&account.activate),
+            bump: account.ids(&account.bump),
+            deactivate: account.ids(&account.deactivate),
+            names: account
+                .offers
+                .iter()
+                .map(|offer| (offer.id, offer.name.clone()))
+                .collect(),
+            configured: KEYS
+                .into_iter()
+                .zip([&account.activate, &account.bump, &account.deactivate])
+                .map(|(key, offers)| {
+                    (
+                        key,
+                        offers
+                            .iter()
+                            .map(|offer| {
+                                (offer.clone(), catalog.resolve(offer).unwrap_or_default())
+                            })
+                            .collect(),
+                    )
+                })
+                .collect(),
+            changed: BTreeSet::new(),
+        }
+    }
+}
+
+/// Write the action lists of the accounts back into a JSON or TOML config file.
+///
+/// The actions are given in the order of the accounts in the file.
+/// Only the lists changed by [`Actions::set`] are written. They keep their groups, tag expressions and
+/// names as long as all of the selected offers are still in the list, other offers are referenced by name where possible.
+/// Empty lists are removed. Any other content, including comments in TOML files, is preserved.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the file could not be read, parsed or written,
+/// or if it does not contain the accounts.
+pub fn write_actions(config_file: &Path, actions: &[Actions]) -> anyhow::Result<()> {
+    let content = read_to_string(config_file)?;
+    let missing = |index: usize| anyhow!("Account #{index} not found in {}", config_file.display());
+
+    let content = if config_file
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"))
+    {
+        let mut document: Value = serde_json::from_str(&content)?;
+
+        for (index, actions) in actions.iter().enumerate() {
+            let account = document
+                .get_mut("accounts")
+                .and_then(|accounts| accounts.get_mut(index))
+                .and_then(Value::as_object_mut)
+                .ok_or_else(|| missing(index))?;
+
+            for (key, offers) in actions.lists() {
+                if offers.is_empty() {
+                    account.remove(key);
+                } else {
+                    account.insert(key.to_string(), serde_json::to_value(offers)?);
+                }
+            }
+        }
+
+        serde_json::to_string_pretty(&document)?
+    } else {
+        let mut document: DocumentMut = content.parse()?;
+
+        for (index, actions) in actions.iter().enumerate() {
+            let account = document
+                .get_mut("accounts")
+                .and_then(|accounts| accounts.get_mut(index))
+                .and_then(Item::as_table_like_mut)
+                .ok_or_else(|| missing(index))?;
+
+            for (key, offers) in actions.lists() {
+                if offers.is_empty() {
+                    account.remove(key);
+                } else {
+                    let mut value = toml_edit::Value::Array(
+                        offers
+                            .into_iter()
+                            .map(|offer| match offer {
+                                OfferRef::Id(id) => toml_edit::Value::from(i64::from(id)),
+                                OfferRef::Name(name) => toml_edit::Value::from(name),
+                                other => toml_edit::Value::from(other.to_string()),
+                            })
+                            .collect::<Array>(),
+                    );
+
+                    // Keep the comments around a replaced list.
+                    if let Some(previous) = account.get(key).and_then(Item::as_value) {
+                        *value.decor_mut() = previous.decor().clone();
+                    }
+
+                    account.insert(key, Item::Value(value));
+                }
+            }
+        }
+
+        document.to_string()
+    };
+
+    Ok(write(config_file, content)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{read_to_string, remove_file, write};
+    use std::path::PathBuf;
+
+    use super::{Actions, write_actions};
+    use crate::config_file::ConfigFile;
+    use crate::offer_ref::OfferRef;
+    use crate::reconcile::Operation;
+
+    const CONFIG: &str = r#"# Accounts to update
+[[accounts]]
+user_name = "user@example.com" # the login
+password = "secret"
+activate = ["@winter", 4] # activated every run
+bump = ["tag:haus-a"]
+deactivate = [ 5 ]
+
+[accounts.groups]
+winter = ["altona", 3]
+
+[[accounts.offers]]
+id = 1
+name = "altona"
+tags = ["haus-a"]
+
+[[accounts.offers]]
+id = 2
+name = "ottensen"
+tags = ["haus-a"]
+"#;
+
+    /// Return a temporary path unique to the calling test, since tests run in parallel.
+    fn path(extension: &str) -> PathBuf {
+        let test = std::thread::current()
+            .name()
+            .unwrap_or("test")
+            .replace("::", "-");
+        std::env::temp_dir().join(format!(
+            "wg_gesucht_updater-actions-{test}-{}.{extension}",
+            std::process::id()
+        ))
+    }
+
+    /// Load the actions of the first account of the config file.
+    fn load(extension: &str, content: &str) -> Actions {
+        let path = path(extension);
+        write(&path, content).expect("Config file should be written.");
+        let config_file = ConfigFile::load(&path);
+        remove_file(&path).expect("Config file should be removed.");
+        (&config_file.expect("Config file should be valid.").accounts[0]).into()
+    }
+
+    /// Write the actions into the config file and return its new content.
+    fn write_config(extension: &str, content: &str, actions: &Actions) -> String {
+        let path = path(extension);
+        write(&path, content).expect("Config file should be written.");
+        let result = write_actions(&path, std::slice::from_ref(actions));
+        let content = read_to_string(&path).expect("Config file should be readable.");
+        remove_file(&path).expect("Config file should be removed.");
+        result.expect("Actions should be written.");
+        content
+    }
+
+    #[test]
+    fn set() {
+        let mut actions = load("toml", CONFIG);
+        assert_eq!(actions.activate, [1, 3, 4]);
+        assert_eq!(actions.bump, [1, 2]);
+        assert_eq!(actions.operation(4), Some(Operation::Activate));
+
+        actions.set(4, Some(Operation::Activate));
+        assert!(actions.changed.is_empty());
+
+        actions.set(4, Some(Operation::Deactivate));
+        assert_eq!(actions.activate, [1, 3]);
+        assert_eq!(actions.deactivate, [5, 4]);
+        assert_eq!(actions.operation(4), Some(Operation::Deactivate));
+
+        actions.set(5, None);
+        assert_eq!(actions.deactivate, [4]);
+        assert_eq!(actions.operation(5), None);
+        assert_eq!(
+            actions.changed.iter().copied().collect::<Vec<_>>(),
+            ["activate", "deactivate"]
+        );
+    }
+
+    #[test]
+    fn lists() {
+        let mut actions = load("toml", CONFIG);
+        actions.set(3, Some(Operation::Bump));
+        assert_eq!(
+            actions.lists(),
+            [
+                (
+                    "activate",
+                    vec![OfferRef::Name("altona".to_string()), OfferRef::Id(4)]
+                ),
+                (
+                    "bump",
+                    vec![
+                        "tag:haus-a".parse().expect("Reference should be valid."),
+                        OfferRef::Id(3)
+                    ]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn write_toml() {
+        let mut actions = load("toml", CONFIG);
+        actions.set(4, Some(Operation::Bump));
+        assert_eq!(
+            write_config("toml", CONFIG, &actions),
+            CONFIG
+                .replace(r#"activate = ["@winter", 4]"#, r#"activate = ["@winter"]"#)
+                .replace(r#"bump = ["tag:haus-a"]"#, r#"bump = ["tag:haus-a", 4]"#)
+        );
+    }
+
+    #[test]
+    fn write_json() {
+        let config = r#"{"accounts": [{"user_name": "user@example.com", "password": "secret", "activate": [2], "deactivate": [3], "offers": [{"id": 1, "name": "altona"}]}]}"#;
+        let mut actions = load("json", config);
+        actions.set(1, Some(Operation::Bump));
+        actions.set(3, None);
+        assert_eq!(
+            write_config("json", config, &actions),
+            r#"{
+  "accounts": [
+    {
+      "activate": [
+        2
+      ],
+      "bump": [
+        "altona"
+      ],
+      "offers": [
+        {
+          "id": 1,
+          "name": "altona"
+        }
+      ],
+      "password": "secret",
+      "user_name": "user@example.com"
+    }
+  ]
+}"#
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+use crate::conversation::Conversation;
+use crate::template::render;
+
+/// Automatic first reply to applicants of the given offers.
+///
+/// The template is rendered with the configured variables as well as
+/// `applicant`, `offer_title` and `casting_date`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct AutoReply {
+    pub(crate) offers: Vec<u32>,
+    pub(crate) template: String,
+    #[serde(default)]
+    pub(crate) casting_dates: Vec<NaiveDateTime>,
+    #[serde(default)]
+    pub(crate) variables: BTreeMap<String, String>,
+}
+
+impl AutoReply {
+    /// Return the earliest casting date after `now`.
+    #[must_use]
+    pub fn next_casting_date(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        self.casting_dates
+            .iter()
+            .filter(|&&date| date > now)
+            .min()
+            .copied()
+    }
+
+    /// Render the reply to a conversation.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] if the template does not exist or could not be rendered.
+    pub fn render(
+        &self,
+        templates: &BTreeMap<String, String>,
+        conversation: &Conversation,
+        offer_title: Option<&str>,
+        now: NaiveDateTime,
+    ) -> anyhow::Result<String> {
+        let template = templates
+            .get(&self.template)
+            .ok_or_else(|| anyhow!("No such template: {}", self.template))?;
+        let mut variables = self.variables.clone();
+        variables.insert("applicant".into(), conversation.sender.clone());
+
+        if let Some(offer_title) = offer_title {
+            variables.insert("offer_title".into(), offer_title.into());
+        }
+
+        if let Some(casting_date) = self.next_casting_date(now) {
+            variables.insert(
+                "casting_date".into(),
+                casting_date.format("%d.%m.%Y %H:%M").to_string(),
+            );
+        }
+
+        render(template, &variables)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use chrono::NaiveDateTime;
+
+    use super::AutoReply;
+    use crate::conversation::Conversation;
+
+    fn date(date: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").expect("valid date")
+    }
+
+    fn auto_reply(casting_dates: &[&str]) -> AutoReply {
+        AutoReply {
+            offers: vec![42],
+            template: "reply".to_string(),
+            casting_dates: casting_dates
+                .iter()
+                .map(|casting_date| date(casting_date))
+                .collect(),
+            variables: [("name".to_string(), "Alex".to_string())].into(),
+        }
+    }
+
+    fn conversation() -> Conversation {
+        Conversation {
+            id: 1,
+            offer: Some(42),
+            sender: "Kim Meyer".to_string(),
+            profile: None,
+            first_message: String::new(),
+            first_contact: None,
+            timestamp: None,
+            unread: true,
+            last_sender: None,
+        }
+    }
+
+    fn templates(template: &str) -> BTreeMap<String, String> {
+        [("reply".to_string(), template.to_string())].into()
+    }
+
+    #[test]
+    fn next_casting_date() {
+        let auto_reply = auto_reply(&["2026-03-10 18:00", "2026-03-03 18:00", "2026-03-01 18:00"]);
+        assert_eq!(
+            auto_reply.next_casting_date(date("2026-03-02 12:00")),
+            Some(date("2026-03-03 18:00"))
+        );
+        assert_eq!(
+            auto_reply.next_casting_date(date("2026-03-03 18:00")),
+            Some(date("2026-03-10 18:00"))
+        );
+        assert_eq!(auto_reply.next_casting_date(date("2026-03-10 18:00")), None);
+        assert_eq!(
+            AutoReply {
+                casting_dates: Vec::new(),
+                ..auto_reply
+            }
+            .next_casting_date(date("2026-03-02 12:00")),
+            None
+        );
+    }
+
+    #[test]
+    fn render() {
+        let auto_reply = auto_reply(&["2026-03-03 18:00"]);
+        assert_eq!(
+            auto_reply
+                .render(
+                    &templates(
+                        "Hi {{applicant}}, thanks for your interest in {{offer_title}}! \
+                         Come by on {{casting_date}}. {{name}}"
+                    ),
+                    &conversation(),
+                    Some("Bright room"),
+                    date("2026-03-02 12:00"),
+                )
+                .expect("rendered reply"),
+            "Hi Kim Meyer, thanks for your interest in Bright room! Come by on 03.03.2026 18:00. Alex"
+        );
+        assert!(
+            auto_reply
+                .render(
+                    &templates("{{casting_date}}"),
+                    &conversation(),
+                    None,
+                    date("2026-03-04 12:00"),
+                )
+                .is_err()
+        );
+        assert!(
+            auto_reply
+                .render(
+                    &templates("{{offer_title}}"),
+                    &conversation(),
+                    None,
+                    date("2026-03-02 12:00"),
+                )
+                .is_err()
+        );
+        assert!(
+            auto_reply
+                .render(
+                    &BTreeMap::new(),
+                    &conversation(),
+                    None,
+                    date("2026-03-02 12:00")
+                )
+                .is_err()
+        );
+    }
+}
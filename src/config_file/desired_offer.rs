@@ -0,0 +1,31 @@
+use serde::Deserialize;
+
+use crate::offer_ref::OfferRef;
+
+/// Desired state of an offer.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct DesiredOffer {
+    pub(crate) id: u32,
+    pub(crate) state: DesiredState,
+}
+
+/// Desired state of the referenced offers as configured.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct DesiredOffers {
+    #[serde(rename = "id", alias = "offers")]
+    pub(crate) offers: OfferRef,
+    #[serde(flatten)]
+    pub(crate) state: DesiredState,
+}
+
+/// States an offer can be reconciled to.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum DesiredState {
+    /// The offer shall be active.
+    Active,
+    /// The offer shall be inactive.
+    Inactive,
+    /// The offer shall be active and have been bumped within the last `max_age_min` minutes.
+    Fresh { max_age_min: u64 },
+}
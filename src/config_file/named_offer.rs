@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+/// An offer with a human-readable name and optional tags.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct NamedOffer {
+    pub(crate) id: u32,
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) tags: Vec<String>,
+}
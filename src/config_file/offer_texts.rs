@@ -0,0 +1,49 @@
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use serde::Deserialize;
+
+use crate::offer_update::OfferUpdate;
+use crate::template::render;
+
+/// Templated description texts of an offer.
+///
+/// Each text field refers to a template by name, which is rendered with the offer's variables.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct OfferTexts {
+    pub(crate) offer: u32,
+    #[serde(default)]
+    pub(crate) variables: BTreeMap<String, String>,
+    pub(crate) property: Option<String>,
+    pub(crate) area: Option<String>,
+    pub(crate) flatshare: Option<String>,
+    pub(crate) other: Option<String>,
+}
+
+impl OfferTexts {
+    /// Render the texts into an offer update.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] if a template does not exist or could not be rendered.
+    pub fn render(&self, templates: &BTreeMap<String, String>) -> anyhow::Result<OfferUpdate> {
+        let render = |name: &Option<String>| -> anyhow::Result<Option<String>> {
+            name.as_ref()
+                .map(|name| {
+                    templates
+                        .get(name)
+                        .ok_or_else(|| anyhow!("No such template: {name}"))
+                        .and_then(|template| render(template, &self.variables))
+                })
+                .transpose()
+        };
+
+        Ok(OfferUpdate {
+            freetext_property_description: render(&self.property)?,
+            freetext_area_description: render(&self.area)?,
+            freetext_flatshare: render(&self.flatshare)?,
+            freetext_other: render(&self.other)?,
+            ..OfferUpdate::default()
+        })
+    }
+}
@@ -0,0 +1,10 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+/// Local directory whose images shall be mirrored to an offer's gallery.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct PhotoDirectory {
+    pub(crate) offer: u32,
+    pub(crate) directory: PathBuf,
+}
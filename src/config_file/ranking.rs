@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+use super::SearchQuery;
+
+/// Monitoring of the positions of an account's offers in the public search.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct Ranking {
+    #[serde(flatten)]
+    pub(crate) query: SearchQuery,
+    pub(crate) pages: Option<u32>,
+    pub(crate) bump_below: Option<usize>,
+}
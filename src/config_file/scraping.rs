@@ -0,0 +1,135 @@
+use std::fmt::{Display, Formatter};
+use std::fs::{create_dir_all, write};
+use std::path::PathBuf;
+
+use chrono::Utc;
+use serde::Deserialize;
+
+/// Fields of a login response of which a truthy one announces the verification step.
+const VERIFICATION_FIELDS: [&str; 2] = ["verification_required", "two_factor_required"];
+
+/// Strategies to scrape the session data from the offers page.
+///
+/// The strategies of each field are tried in order until one of them yields a value.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Scraping {
+    pub(crate) csrf_token: Vec<Strategy>,
+    pub(crate) user_id: Vec<Strategy>,
+    pub(crate) diagnostics_dir: Option<PathBuf>,
+    pub(crate) verification: Option<VerificationEndpoint>,
+}
+
+/// Endpoint of the second login step.
+///
+/// The step has not been observed on wg-gesucht.de yet, so there is no default endpoint and a
+/// login asking for a verification code fails unless one is configured.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct VerificationEndpoint {
+    /// The URL the verification code is posted to.
+    pub(crate) url: String,
+    /// The fields of the login response of which a truthy one asks for a verification code.
+    #[serde(default = "default_verification_fields")]
+    pub(crate) fields: Vec<String>,
+}
+
+/// A strategy to find a value within an HTML page.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Strategy {
+    /// Read an attribute or, if none is given, the text of the first element matching a CSS selector.
+    Css {
+        /// The CSS selector.
+        selector: String,
+        /// The attribute to read.
+        attribute: Option<String>,
+    },
+    /// Read the content of a `<meta>` tag with the given name or property.
+    Meta {
+        /// The name or property of the tag.
+        name: String,
+    },
+    /// Read a string or number assigned to a variable or key in an inline script.
+    Js {
+        /// The name of the variable or key.
+        variable: String,
+    },
+}
+
+impl Scraping {
+    /// Save a page that could not be scraped to the diagnostics directory, if configured.
+    ///
+    /// Return the path of the saved page.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`std::io::Error`] if the page could not be written.
+    pub fn save_page(&self, name: &str, page: &str) -> std::io::Result<Option<PathBuf>> {
+        let Some(diagnostics_dir) = &self.diagnostics_dir else {
+            return Ok(None);
+        };
+        create_dir_all(diagnostics_dir)?;
+        let path = diagnostics_dir.join(format!(
+            "{name}-{}.html",
+            Utc::now().format("%Y%m%dT%H%M%S")
+        ));
+        write(&path, page)?;
+        Ok(Some(path))
+    }
+}
+
+impl Default for Scraping {
+    fn default() -> Self {
+        Self {
+            csrf_token: vec![
+                Strategy::css("a[data-csrf_token]", "data-csrf_token"),
+                Strategy::Meta {
+                    name: "csrf-token".into(),
+                },
+                Strategy::Js {
+                    variable: "csrf_token".into(),
+                },
+            ],
+            user_id: vec![
+                Strategy::css("a[data-user_id]", "data-user_id"),
+                Strategy::Js {
+                    variable: "user_id".into(),
+                },
+            ],
+            diagnostics_dir: None,
+            verification: None,
+        }
+    }
+}
+
+/// Return the fields assumed to announce the verification step unless configured otherwise.
+#[must_use]
+pub fn default_verification_fields() -> Vec<String> {
+    VERIFICATION_FIELDS.map(ToString::to_string).to_vec()
+}
+
+impl Strategy {
+    fn css(selector: &str, attribute: &str) -> Self {
+        Self::Css {
+            selector: selector.into(),
+            attribute: Some(attribute.into()),
+        }
+    }
+}
+
+impl Display for Strategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Css {
+                selector,
+                attribute: Some(attribute),
+            } => write!(f, "attribute {attribute:?} of CSS selector {selector:?}"),
+            Self::Css {
+                selector,
+                attribute: None,
+            } => write!(f, "text of CSS selector {selector:?}"),
+            Self::Meta { name } => write!(f, "meta tag {name:?}"),
+            Self::Js { variable } => write!(f, "script variable {variable:?}"),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use serde::Deserialize;
+
+/// Query against the public search for offers.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct SearchQuery {
+    pub(crate) city_id: u32,
+    #[serde(default)]
+    pub(crate) category: Category,
+    pub(crate) rent_min: Option<u32>,
+    pub(crate) rent_max: Option<u32>,
+}
+
+/// Category of offers to search for.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    /// Rooms in flatshares.
+    #[default]
+    Flatshare,
+    /// One-room flats.
+    OneRoomFlat,
+    /// Flats.
+    Flat,
+    /// Houses.
+    House,
+}
+
+impl Category {
+    /// Return the ID of the category as used in search URLs.
+    #[must_use]
+    pub const fn id(self) -> u8 {
+        match self {
+            Self::Flatshare => 0,
+            Self::OneRoomFlat => 1,
+            Self::Flat => 2,
+            Self::House => 3,
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use serde::Deserialize;
+
+use super::SearchQuery;
+
+/// A public search to watch for new listings.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct Watch {
+    pub(crate) name: String,
+    #[serde(flatten)]
+    pub(crate) query: SearchQuery,
+    pub(crate) pages: Option<u32>,
+    pub(crate) user_agent: Option<String>,
+    pub(crate) timeout_sec: Option<u64>,
+}
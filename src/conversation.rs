@@ -0,0 +1,177 @@
+use std::fmt::{Display, Formatter};
+
+use anyhow::anyhow;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::diff::value_to_string;
+
+const PREVIEW_LENGTH: usize = 80;
+
+/// A conversation in the messaging inbox.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Conversation {
+    pub(crate) id: u32,
+    pub(crate) offer: Option<u32>,
+    pub(crate) sender: String,
+    pub(crate) profile: Option<String>,
+    pub(crate) first_message: String,
+    pub(crate) first_contact: Option<DateTime<Utc>>,
+    pub(crate) timestamp: Option<DateTime<Utc>>,
+    pub(crate) unread: bool,
+    pub(crate) last_sender: Option<String>,
+}
+
+impl TryFrom<Map<String, Value>> for Conversation {
+    type Error = anyhow::Error;
+
+    fn try_from(map: Map<String, Value>) -> Result<Self, Self::Error> {
+        let field = |name: &str| map.get(name).and_then(value_to_string);
+        Ok(Self {
+            id: field("conversation_id")
+                .ok_or_else(|| anyhow!("Conversation lacks an ID"))?
+                .parse()?,
+            offer: field("ad_id").and_then(|id| id.parse().ok()),
+            sender: [field("firstname"), field("lastname")]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" "),
+            profile: field("profile_url"),
+            first_message: field("first_message").unwrap_or_default(),
+            first_contact: field("creation_date").as_deref().and_then(parse_timestamp),
+            timestamp: field("last_message_timestamp")
+                .as_deref()
+                .and_then(parse_timestamp),
+            unread: field("unread").is_some_and(|unread| unread != "0"),
+            last_sender: field("last_message_user_id"),
+        })
+    }
+}
+
+impl Display for Conversation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", if self.unread { "* " } else { "  " })?;
+
+        if let Some(timestamp) = self.timestamp {
+            write!(f, "{} ", timestamp.format("%Y-%m-%d %H:%M"))?;
+        }
+
+        let preview: String = self
+            .first_message
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .take(PREVIEW_LENGTH)
+            .collect();
+        write!(f, "{}: {preview}", self.sender)?;
+
+        if self.first_message.chars().count() > PREVIEW_LENGTH {
+            write!(f, "…")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse either a UNIX timestamp or a timestamp of the form `YYYY-MM-DD HH:MM:SS`.
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    timestamp.parse().map_or_else(
+        |_| {
+            NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|timestamp| timestamp.and_utc())
+        },
+        |seconds| DateTime::from_timestamp(seconds, 0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+    use serde_json::{Map, Value, json};
+
+    use super::{Conversation, parse_timestamp};
+
+    fn conversation(value: Value) -> anyhow::Result<Conversation> {
+        Conversation::try_from(serde_json::from_value::<Map<String, Value>>(value)?)
+    }
+
+    #[test]
+    fn try_from() {
+        assert_eq!(
+            conversation(json!({
+                "conversation_id": "7",
+                "ad_id": 42,
+                "firstname": "Kim",
+                "lastname": null,
+                "profile_url": "https://www.wg-gesucht.de/user/7",
+                "first_message": "Hallo!",
+                "creation_date": "2026-03-01 12:30:00",
+                "last_message_timestamp": "1772368200",
+                "unread": "1",
+                "last_message_user_id": "1234567",
+            }))
+            .expect("valid conversation"),
+            Conversation {
+                id: 7,
+                offer: Some(42),
+                sender: "Kim".to_string(),
+                profile: Some("https://www.wg-gesucht.de/user/7".to_string()),
+                first_message: "Hallo!".to_string(),
+                first_contact: DateTime::from_timestamp(1_772_368_200, 0),
+                timestamp: DateTime::from_timestamp(1_772_368_200, 0),
+                unread: true,
+                last_sender: Some("1234567".to_string()),
+            }
+        );
+        let minimal = conversation(json!({"conversation_id": 8, "unread": "0"}))
+            .expect("minimal conversation");
+        assert_eq!(minimal.sender, "");
+        assert!(!minimal.unread);
+        assert!(conversation(json!({"firstname": "Kim"})).is_err());
+        assert!(conversation(json!({"conversation_id": "x"})).is_err());
+    }
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(
+            parse_timestamp("1772368200"),
+            DateTime::from_timestamp(1_772_368_200, 0)
+        );
+        assert_eq!(
+            parse_timestamp("2026-03-01 12:30:00"),
+            DateTime::from_timestamp(1_772_368_200, 0)
+        );
+        assert_eq!(parse_timestamp("01.03.2026"), None);
+        assert_eq!(parse_timestamp(""), None);
+    }
+
+    #[test]
+    fn preview() {
+        let message = |first_message: &str| {
+            conversation(json!({
+                "conversation_id": 1,
+                "firstname": "Kim",
+                "first_message": first_message,
+                "last_message_timestamp": "2026-03-01 12:30:00",
+            }))
+            .expect("valid conversation")
+            .to_string()
+        };
+        assert_eq!(
+            message("Hallo,\n\n  ich  bin Kim."),
+            "  2026-03-01 12:30 Kim: Hallo, ich bin Kim."
+        );
+        assert_eq!(
+            message(&"ä".repeat(81)),
+            format!("  2026-03-01 12:30 Kim: {}…", "ä".repeat(80))
+        );
+        assert_eq!(
+            message(&"ä".repeat(80)),
+            format!("  2026-03-01 12:30 Kim: {}", "ä".repeat(80))
+        );
+    }
+}
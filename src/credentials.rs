@@ -0,0 +1,156 @@
+use std::io::{IsTerminal, Write, stderr, stdin};
+
+use anyhow::anyhow;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+const TOTP_DIGITS: usize = 6;
+const TOTP_SKEW: u8 = 1;
+const TOTP_STEP_SEC: u64 = 30;
+
+/// Credentials to log into an account.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Credentials {
+    pub(crate) user_name: String,
+    pub(crate) password: String,
+    pub(crate) autologin: bool,
+    pub(crate) verification: Verification,
+}
+
+/// Source of the verification code if the login requires a second step.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum Verification {
+    /// Ask for the code on the terminal.
+    #[default]
+    Prompt,
+    /// Use a code that has been passed in.
+    Code(String),
+    /// Generate the code from a base32 encoded TOTP secret.
+    TotpSecret(String),
+}
+
+impl Credentials {
+    pub const fn new(
+        user_name: String,
+        password: String,
+        autologin: bool,
+        verification: Verification,
+    ) -> Self {
+        Self {
+            user_name,
+            password,
+            autologin,
+            verification,
+        }
+    }
+}
+
+impl Verification {
+    /// Return the verification code for the given account.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] if the code could not be generated or read from the terminal.
+    pub fn code(&self, user_name: &str) -> anyhow::Result<String> {
+        match self {
+            Self::Prompt => prompt(user_name),
+            Self::Code(code) => Ok(code.clone()),
+            Self::TotpSecret(secret) => Ok(totp(secret)?.generate_current()?),
+        }
+    }
+
+    /// Return the verification code for logging in again without user interaction.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] unless the code is generated from a TOTP secret,
+    /// since a code passed in has already been used and there may be nobody to prompt.
+    pub fn relogin_code(&self, user_name: &str) -> anyhow::Result<String> {
+        match self {
+            Self::TotpSecret(_) => self.code(user_name),
+            Self::Prompt | Self::Code(_) => Err(anyhow!(
+                "Login of {user_name} requires a verification code again, which requires a TOTP secret"
+            )),
+        }
+    }
+}
+
+fn totp(secret: &str) -> anyhow::Result<TOTP> {
+    Ok(TOTP::new_unchecked(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        TOTP_SKEW,
+        TOTP_STEP_SEC,
+        Secret::Encoded(secret.replace(' ', "").to_uppercase())
+            .to_bytes()
+            .map_err(|error| anyhow!("Invalid TOTP secret: {error:?}"))?,
+    ))
+}
+
+fn prompt(user_name: &str) -> anyhow::Result<String> {
+    if !stdin().is_terminal() {
+        return Err(anyhow!(
+            "Login of {user_name} requires a verification code, but there is no terminal to ask for it"
+        ));
+    }
+
+    eprint!("Verification code for {user_name}: ");
+    stderr().flush()?;
+    let mut code = String::new();
+    stdin().read_line(&mut code)?;
+    Ok(code.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Verification, totp};
+
+    /// The RFC 6238 SHA1 secret "12345678901234567890" encoded as base32.
+    const SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn totp_codes() {
+        let totp = totp(SECRET).expect("Secret should be valid.");
+        assert_eq!(totp.generate(59), "287082");
+        assert_eq!(totp.generate(1_111_111_109), "081804");
+        assert_eq!(totp.generate(2_000_000_000), "279037");
+    }
+
+    #[test]
+    fn totp_secret_normalization() {
+        assert_eq!(
+            totp("gezd gnbv gy3t qojq gezd gnbv gy3t qojq")
+                .expect("Secret should be valid.")
+                .generate(59),
+            "287082"
+        );
+    }
+
+    #[test]
+    fn invalid_totp_secret() {
+        assert!(totp("not base32!").is_err());
+    }
+
+    #[test]
+    fn relogin_code() {
+        assert_eq!(
+            Verification::TotpSecret(SECRET.to_string())
+                .relogin_code("user")
+                .expect("TOTP code should be generated.")
+                .len(),
+            6
+        );
+
+        for verification in [
+            Verification::Prompt,
+            Verification::Code("123456".to_string()),
+        ] {
+            assert_eq!(
+                verification
+                    .relogin_code("user")
+                    .expect_err("Code should not be reused.")
+                    .to_string(),
+                "Login of user requires a verification code again, which requires a TOTP secret"
+            );
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct CsrfData<'token> {
+    csrf_token: &'token str,
+}
+
+impl<'token> CsrfData<'token> {
+    pub const fn new(csrf_token: &'token str) -> Self {
+        Self { csrf_token }
+    }
+}
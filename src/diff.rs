@@ -0,0 +1,61 @@
+use std::fmt::{Display, Formatter};
+
+use serde_json::{Map, Value};
+
+/// A change of a single field of an offer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Change {
+    field: String,
+    old: Option<String>,
+    new: String,
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}:", self.field)?;
+
+        if let Some(old) = &self.old {
+            for line in old.lines() {
+                writeln!(f, "- {line}")?;
+            }
+        }
+
+        for line in self.new.lines() {
+            writeln!(f, "+ {line}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Return the changes the given fields would apply to an offer with the given details.
+pub fn diff<F, V>(
+    current: &Map<String, Value>,
+    fields: impl IntoIterator<Item = (F, V)>,
+) -> Vec<Change>
+where
+    F: AsRef<str>,
+    V: AsRef<str>,
+{
+    fields
+        .into_iter()
+        .filter_map(|(field, new)| {
+            let old = current.get(field.as_ref()).and_then(value_to_string);
+            (old.as_deref() != Some(new.as_ref())).then(|| Change {
+                field: field.as_ref().to_string(),
+                old,
+                new: new.as_ref().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Return the string representation of a JSON value or `None` if it is `null`.
+#[must_use]
+pub fn value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(string) => Some(string.clone()),
+        other => Some(other.to_string()),
+    }
+}
@@ -0,0 +1,14 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct EditData<'data, T> {
+    #[serde(flatten)]
+    update: &'data T,
+    csrf_token: &'data str,
+}
+
+impl<'data, T> EditData<'data, T> {
+    pub const fn new(update: &'data T, csrf_token: &'data str) -> Self {
+        Self { update, csrf_token }
+    }
+}
@@ -9,6 +9,8 @@ mod failed_updates;
 pub enum Error {
     /// An error occurred during login.
     Login(anyhow::Error),
+    /// The offers could not be listed.
+    Listing(anyhow::Error),
     /// Some offers failed to update.
     Updates(Box<FailedUpdates>),
 }
@@ -17,6 +19,7 @@ impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Login(error) => write!(f, "Login failed: {error}"),
+            Self::Listing(error) => write!(f, "Could not list offers: {error}"),
             Self::Updates(updates) => Display::fmt(updates, f),
         }
     }
@@ -25,7 +28,7 @@ impl Display for Error {
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            Self::Login(_) => None,
+            Self::Login(_) | Self::Listing(_) => None,
             Self::Updates(error) => Some(error),
         }
     }
@@ -1,38 +1,129 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Display, Formatter};
 
+use crate::catalog::Catalog;
+
 /// Details about failed updates.
 #[derive(Debug, Default)]
 pub struct FailedUpdates {
     pub(crate) activate: BTreeMap<u32, anyhow::Error>,
     pub(crate) deactivate: BTreeMap<u32, anyhow::Error>,
     pub(crate) bump: BTreeMap<u32, anyhow::Error>,
+    pub(crate) listing: Option<anyhow::Error>,
+}
+
+/// Failed updates displayed with the names of the offers.
+#[derive(Debug)]
+pub struct Labeled<'updates> {
+    failed_updates: &'updates FailedUpdates,
+    catalog: &'updates Catalog,
 }
 
 impl FailedUpdates {
     /// Return `true` iff there are no errors.
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.activate.is_empty() && self.deactivate.is_empty() && self.bump.is_empty()
+        self.activate.is_empty()
+            && self.deactivate.is_empty()
+            && self.bump.is_empty()
+            && self.listing.is_none()
+    }
+
+    /// Return the failed updates for display with the names of the offers in the catalog.
+    #[must_use]
+    pub const fn labeled<'updates>(
+        &'updates self,
+        catalog: &'updates Catalog,
+    ) -> Labeled<'updates> {
+        Labeled {
+            failed_updates: self,
+            catalog,
+        }
+    }
+
+    /// Return the IDs of all offers that failed to update.
+    #[must_use]
+    pub fn offers(&self) -> BTreeSet<u32> {
+        self.activate
+            .keys()
+            .chain(self.deactivate.keys())
+            .chain(self.bump.keys())
+            .copied()
+            .collect()
     }
 }
 
 impl Display for FailedUpdates {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for (id, error) in &self.activate {
-            write!(f, "Failed to activate #{id}: {error}")?;
-        }
+        Display::fmt(&self.labeled(&Catalog::default()), f)
+    }
+}
+
+impl Display for Labeled<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let failed_updates = self.failed_updates;
+        let mut lines = Vec::new();
 
-        for (id, error) in &self.deactivate {
-            write!(f, "Failed to deactivate #{id}: {error}")?;
+        for (operation, failures) in [
+            ("activate", &failed_updates.activate),
+            ("deactivate", &failed_updates.deactivate),
+            ("bump", &failed_updates.bump),
+        ] {
+            for (id, error) in failures {
+                lines.push(format!(
+                    "Failed to {operation} {}: {error}",
+                    self.catalog.label(*id)
+                ));
+            }
         }
 
-        for (id, error) in &self.bump {
-            write!(f, "Failed to bump #{id}: {error}")?;
+        if let Some(error) = &failed_updates.listing {
+            lines.push(format!("Could not list offers: {error}"));
         }
 
-        Ok(())
+        write!(f, "{}", lines.join("\n"))
     }
 }
 
 impl std::error::Error for FailedUpdates {}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::FailedUpdates;
+    use crate::catalog::Catalog;
+    use crate::config_file::NamedOffer;
+
+    #[test]
+    fn display() {
+        let failed_updates = FailedUpdates {
+            activate: [(1, anyhow!("timeout"))].into(),
+            deactivate: [(2, anyhow!("500"))].into(),
+            bump: [(3, anyhow!("401")), (4, anyhow!("404"))].into(),
+            listing: Some(anyhow!("no page")),
+        };
+        let catalog = Catalog::new(
+            vec![NamedOffer {
+                id: 3,
+                name: "altona".to_string(),
+                tags: Vec::new(),
+            }],
+            [].into(),
+        );
+        assert_eq!(
+            failed_updates.labeled(&catalog).to_string(),
+            "Failed to activate #1: timeout\n\
+             Failed to deactivate #2: 500\n\
+             Failed to bump #3 (altona): 401\n\
+             Failed to bump #4: 404\n\
+             Could not list offers: no page"
+        );
+        assert!(
+            failed_updates
+                .to_string()
+                .contains("Failed to bump #3: 401\n")
+        );
+        assert_eq!(FailedUpdates::default().to_string(), "");
+    }
+}
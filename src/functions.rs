@@ -1,5 +1,9 @@
+use chrono::NaiveDate;
 use serde::Serializer;
 
+/// Format of dates as expected by wg-gesucht, e.g. `31.12.2026`.
+pub const GERMAN_DATE_FORMAT: &str = "%d.%m.%Y";
+
 #[allow(clippy::trivially_copy_pass_by_ref)]
 pub fn bool_to_int_str<S>(value: &bool, serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -7,3 +11,14 @@ where
 {
     serializer.serialize_str(if *value { "1" } else { "0" })
 }
+
+#[allow(clippy::trivially_copy_pass_by_ref, clippy::ref_option)]
+pub fn date_to_german_str<S>(value: &Option<NaiveDate>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(date) => serializer.serialize_str(&date.format(GERMAN_DATE_FORMAT).to_string()),
+        None => serializer.serialize_none(),
+    }
+}
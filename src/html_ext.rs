@@ -1,41 +1,472 @@
 use std::sync::LazyLock;
 
 use anyhow::anyhow;
-use scraper::{Html, Selector};
+use chrono::{DateTime, Utc};
+use log::{debug, warn};
+use scraper::{ElementRef, Html, Selector};
 
-static CSRF_TOKEN_SELECTOR: LazyLock<Selector> = LazyLock::new(|| {
-    Selector::parse("a[data-csrf_token]").expect("Could not create CSRF token selector")
+use crate::config_file::{Scraping, Strategy};
+use crate::listing::Listing;
+use crate::offer::Offer;
+use crate::offer_stats::OfferStats;
+
+static META_SELECTOR: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("meta").expect("Could not create meta selector"));
+static SCRIPT_SELECTOR: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("script").expect("Could not create script selector"));
+static OFFER_SELECTOR: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("[data-ad_id]").expect("Could not create offer selector"));
+static OFFER_TITLE_SELECTOR: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse(".offer_title").expect("Could not create offer title selector")
+});
+static OFFER_DATE_SELECTOR: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse("time[datetime]").expect("Could not create offer date selector")
+});
+static OFFER_VIEWS_SELECTOR: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse(".offer_views").expect("Could not create offer views selector")
+});
+static OFFER_CONTACTS_SELECTOR: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse(".offer_contacts").expect("Could not create offer contacts selector")
 });
-static USER_ID_SELECTOR: LazyLock<Selector> = LazyLock::new(|| {
-    Selector::parse("a[data-user_id]").expect("Could not create user ID selector")
+static LISTING_SELECTOR: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse(".offer_list_item[data-id]").expect("Could not create listing selector")
+});
+static LISTING_TITLE_SELECTOR: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse(".truncate_title a").expect("Could not create listing title selector")
+});
+static LISTING_RENT_SELECTOR: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse(".middle .col-xs-3 b").expect("Could not create listing rent selector")
+});
+static LISTING_SIZE_SELECTOR: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse(".middle .text-right b").expect("Could not create listing size selector")
 });
 
 /// Extension trait for `Html`.
 pub trait HtmlExt {
-    /// Scrape the CSRF token from the HTML page.
-    fn scrape_csrf_token(&self) -> Option<&str>;
+    /// Scrape a value using the given strategy.
+    fn scrape_strategy(&self, strategy: &Strategy) -> Option<String>;
 
-    /// Scrape the user ID from the HTML page.
-    fn scrape_user_id(&self) -> Option<&str>;
+    /// Scrape a value using the first of the given strategies that yields one.
+    fn scrape_with(&self, strategies: &[Strategy]) -> Option<String> {
+        strategies.iter().find_map(|strategy| {
+            let value = self.scrape_strategy(strategy);
+            debug!("Scraping {strategy}: {value:?}");
+            value
+        })
+    }
+
+    /// Scrape the offers listed on the offers page.
+    fn scrape_offers(&self) -> anyhow::Result<Vec<Offer>>;
+
+    /// Scrape the view and contact counts of the offers listed on the offers page.
+    fn scrape_offer_stats(&self) -> anyhow::Result<Vec<OfferStats>>;
+
+    /// Scrape the listings on a public search results page in their order.
+    ///
+    /// Listings without a valid ID are skipped.
+    fn scrape_listings(&self) -> Vec<Listing>;
 
     /// Scrape the CSRF token and user ID from the HTML page.
-    fn scrape_csrf_token_and_user_id(&self) -> anyhow::Result<(&str, &str)> {
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] listing the tried strategies if a value could not be found.
+    fn scrape_csrf_token_and_user_id(
+        &self,
+        scraping: &Scraping,
+    ) -> anyhow::Result<(String, String)> {
+        let scrape = |name: &str, strategies: &[Strategy]| {
+            self.scrape_with(strategies).ok_or_else(|| {
+                anyhow!(
+                    "Could not find {name}, tried: {}",
+                    strategies
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+        };
         Ok((
-            self.scrape_csrf_token()
-                .ok_or_else(|| anyhow!("Could not find element with CSRF token"))?,
-            self.scrape_user_id()
-                .ok_or_else(|| anyhow!("Could not find element with user ID"))?,
+            scrape("CSRF token", &scraping.csrf_token)?,
+            scrape("user ID", &scraping.user_id)?,
         ))
     }
 }
 
 impl HtmlExt for Html {
-    fn scrape_csrf_token(&self) -> Option<&str> {
-        self.select(&CSRF_TOKEN_SELECTOR)
-            .find_map(|element| element.value().attr("data-csrf_token"))
+    fn scrape_strategy(&self, strategy: &Strategy) -> Option<String> {
+        match strategy {
+            Strategy::Css {
+                selector,
+                attribute,
+            } => {
+                let selector = Selector::parse(selector)
+                    .map_err(|error| warn!("Invalid CSS selector {selector:?}: {error}"))
+                    .ok()?;
+                self.select(&selector).find_map(|element| {
+                    attribute.as_ref().map_or_else(
+                        || Some(element.text().collect::<String>().trim().to_string()),
+                        |attribute| element.value().attr(attribute).map(ToString::to_string),
+                    )
+                })
+            }
+            Strategy::Meta { name } => self.select(&META_SELECTOR).find_map(|element| {
+                let meta = element.value();
+                (meta.attr("name") == Some(name) || meta.attr("property") == Some(name))
+                    .then(|| meta.attr("content").map(ToString::to_string))
+                    .flatten()
+            }),
+            Strategy::Js { variable } => self
+                .select(&SCRIPT_SELECTOR)
+                .find_map(|script| find_js_variable(&script.text().collect::<String>(), variable)),
+        }
+        .filter(|value| !value.is_empty())
+    }
+
+    fn scrape_offers(&self) -> anyhow::Result<Vec<Offer>> {
+        self.select(&OFFER_SELECTOR).map(scrape_offer).collect()
+    }
+
+    fn scrape_offer_stats(&self) -> anyhow::Result<Vec<OfferStats>> {
+        self.select(&OFFER_SELECTOR)
+            .map(scrape_offer_stats)
+            .collect()
+    }
+
+    fn scrape_listings(&self) -> Vec<Listing> {
+        self.select(&LISTING_SELECTOR)
+            .filter_map(|element| {
+                scrape_listing(element)
+                    .map_err(|error| warn!("Skipping listing: {error}"))
+                    .ok()
+            })
+            .collect()
+    }
+}
+
+/// Find the string or number assigned to a variable or key, e.g. `name = "value"` or `"name": 42`.
+fn find_js_variable(script: &str, name: &str) -> Option<String> {
+    let mut rest = script;
+
+    while let Some((before, after)) = rest.split_once(name) {
+        rest = after;
+
+        if before
+            .chars()
+            .next_back()
+            .is_some_and(|char| char.is_alphanumeric() || char == '_' || char == '$')
+        {
+            continue;
+        }
+
+        let Some(value) = after
+            .trim_start_matches(['"', '\''])
+            .trim_start()
+            .strip_prefix(['=', ':'])
+            .filter(|value| !value.starts_with('='))
+            .map(str::trim_start)
+        else {
+            continue;
+        };
+
+        let value = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value
+                .strip_prefix(quote)
+                .and_then(|value| value.split_once(quote))
+                .map(|(value, _)| value.to_string()),
+            _ => Some(
+                value
+                    .chars()
+                    .take_while(|char| char.is_alphanumeric() || *char == '_' || *char == '-')
+                    .collect(),
+            ),
+        };
+
+        if let Some(value) = value.filter(|value| !value.is_empty()) {
+            return Some(value);
+        }
+    }
+
+    None
+}
+
+fn scrape_offer(element: ElementRef<'_>) -> anyhow::Result<Offer> {
+    Ok(Offer {
+        id: scrape_offer_id(element)?,
+        title: element
+            .select(&OFFER_TITLE_SELECTOR)
+            .next()
+            .map(|title| title.text().collect::<String>().trim().to_string()),
+        active: element.value().attr("data-deactivated") != Some("1"),
+        last_bumped: element
+            .select(&OFFER_DATE_SELECTOR)
+            .find_map(|time| time.value().attr("datetime"))
+            .and_then(|datetime| DateTime::parse_from_rfc3339(datetime).ok())
+            .map(|datetime| datetime.with_timezone(&Utc)),
+    })
+}
+
+fn scrape_offer_stats(element: ElementRef<'_>) -> anyhow::Result<OfferStats> {
+    Ok(OfferStats {
+        id: scrape_offer_id(element)?,
+        views: scrape_count(element, &OFFER_VIEWS_SELECTOR),
+        contacts: scrape_count(element, &OFFER_CONTACTS_SELECTOR),
+    })
+}
+
+fn scrape_listing(element: ElementRef<'_>) -> anyhow::Result<Listing> {
+    let title = element.select(&LISTING_TITLE_SELECTOR).next();
+    Ok(Listing {
+        id: element
+            .value()
+            .attr("data-id")
+            .ok_or_else(|| anyhow!("Listing element has no ID"))?
+            .parse()?,
+        title: title.map(|title| title.text().collect::<String>().trim().to_string()),
+        rent: scrape_count(element, &LISTING_RENT_SELECTOR)
+            .and_then(|rent| u32::try_from(rent).ok()),
+        size: scrape_count(element, &LISTING_SIZE_SELECTOR)
+            .and_then(|size| u32::try_from(size).ok()),
+        url: title
+            .and_then(|title| title.value().attr("href"))
+            .map(|href| {
+                if href.starts_with('/') {
+                    format!("https://www.wg-gesucht.de{href}")
+                } else {
+                    href.to_string()
+                }
+            }),
+    })
+}
+
+fn scrape_offer_id(element: ElementRef<'_>) -> anyhow::Result<u32> {
+    Ok(element
+        .value()
+        .attr("data-ad_id")
+        .ok_or_else(|| anyhow!("Offer element has no ID"))?
+        .parse()?)
+}
+
+/// Scrape a count from the first matching element, ignoring any non-digit characters.
+fn scrape_count(element: ElementRef<'_>, selector: &Selector) -> Option<u64> {
+    element
+        .select(selector)
+        .next()
+        .map(|count| {
+            count
+                .text()
+                .flat_map(str::chars)
+                .filter(char::is_ascii_digit)
+                .collect::<String>()
+        })
+        .and_then(|count| count.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use scraper::Html;
+
+    use super::{HtmlExt, OFFER_VIEWS_SELECTOR, find_js_variable, scrape_count};
+    use crate::config_file::{Scraping, Strategy};
+    use crate::listing::Listing;
+    use crate::offer_stats::OfferStats;
+
+    #[test]
+    fn js_variable_assignments() {
+        for (script, expected) in [
+            (r#"var csrf_token = "abc";"#, Some("abc")),
+            ("let csrf_token='abc'", Some("abc")),
+            (r#"window.config = {"csrf_token": "abc"}"#, Some("abc")),
+            ("{csrf_token: 42}", Some("42")),
+            ("csrf_token = abc-def_1;", Some("abc-def_1")),
+            (r#"my_csrf_token = "no"; csrf_token = "yes""#, Some("yes")),
+            (r#"csrf_token_old = "no""#, None),
+            (r#"if (csrf_token == "no") {}"#, None),
+            (r#"csrf_token = ""; csrf_token = "later""#, Some("later")),
+            ("csrf_token", None),
+            ("", None),
+        ] {
+            assert_eq!(
+                find_js_variable(script, "csrf_token").as_deref(),
+                expected,
+                "{script}"
+            );
+        }
     }
-    fn scrape_user_id(&self) -> Option<&str> {
-        self.select(&USER_ID_SELECTOR)
-            .find_map(|element| element.value().attr("data-user_id"))
+
+    #[test]
+    fn css_strategy() {
+        let html = Html::parse_document(
+            r#"<p class="empty" data-id=""></p><p class="token" data-id="abc"> text </p>"#,
+        );
+        let css = |selector: &str, attribute: Option<&str>| Strategy::Css {
+            selector: selector.to_string(),
+            attribute: attribute.map(ToString::to_string),
+        };
+        assert_eq!(
+            html.scrape_strategy(&css("p", Some("data-id"))).as_deref(),
+            None
+        );
+        assert_eq!(
+            html.scrape_strategy(&css(".token", Some("data-id")))
+                .as_deref(),
+            Some("abc")
+        );
+        assert_eq!(
+            html.scrape_strategy(&css(".token", None)).as_deref(),
+            Some("text")
+        );
+        assert_eq!(html.scrape_strategy(&css(".missing", None)), None);
+        assert_eq!(html.scrape_strategy(&css("[[invalid", None)), None);
+    }
+
+    #[test]
+    fn meta_strategy() {
+        let html = Html::parse_document(
+            r#"<head><meta name="csrf-token" content="abc"><meta property="og:user" content="42"></head>"#,
+        );
+        let meta = |name: &str| Strategy::Meta {
+            name: name.to_string(),
+        };
+        assert_eq!(
+            html.scrape_strategy(&meta("csrf-token")).as_deref(),
+            Some("abc")
+        );
+        assert_eq!(
+            html.scrape_strategy(&meta("og:user")).as_deref(),
+            Some("42")
+        );
+        assert_eq!(html.scrape_strategy(&meta("missing")), None);
+    }
+
+    #[test]
+    fn js_strategy() {
+        let html = Html::parse_document(
+            r#"<script>var other = 1;</script><script>var user_id = "42";</script>"#,
+        );
+        let js = |variable: &str| Strategy::Js {
+            variable: variable.to_string(),
+        };
+        assert_eq!(html.scrape_strategy(&js("user_id")).as_deref(), Some("42"));
+        assert_eq!(html.scrape_strategy(&js("missing")), None);
+    }
+
+    #[test]
+    fn count() {
+        for (html, expected) in [
+            (
+                r#"<span class="offer_views">1.234 Aufrufe</span>"#,
+                Some(1_234),
+            ),
+            (r#"<span class="offer_views"> 7 </span>"#, Some(7)),
+            (r#"<span class="offer_views"><b>1</b>2</span>"#, Some(12)),
+            (r#"<span class="offer_views">keine</span>"#, None),
+            (r#"<span class="offer_contacts">3</span>"#, None),
+        ] {
+            let html = Html::parse_fragment(html);
+            assert_eq!(
+                scrape_count(html.root_element(), &OFFER_VIEWS_SELECTOR),
+                expected,
+                "{html:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn offer_stats() {
+        let html = Html::parse_document(
+            r#"<div data-ad_id="42"><span class="offer_views">1.234</span><span class="offer_contacts">5 Kontakte</span></div>
+            <div data-ad_id="43"><span class="offer_views">0</span></div>"#,
+        );
+        assert_eq!(
+            html.scrape_offer_stats().expect("scraped stats"),
+            [
+                OfferStats {
+                    id: 42,
+                    views: Some(1_234),
+                    contacts: Some(5),
+                },
+                OfferStats {
+                    id: 43,
+                    views: Some(0),
+                    contacts: None,
+                },
+            ]
+        );
+        assert!(
+            Html::parse_document(r#"<div data-ad_id="x"></div>"#)
+                .scrape_offer_stats()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn listings() {
+        let html = Html::parse_document(include_str!("../fixtures/search/results.html"));
+        assert_eq!(
+            html.scrape_listings(),
+            [
+                Listing {
+                    id: 11_111_111,
+                    title: Some("Helles Zimmer in Altona".to_string()),
+                    rent: Some(520),
+                    size: Some(14),
+                    url: Some(
+                        "https://www.wg-gesucht.de/wg-zimmer-in-Hamburg-Altona.11111111.html"
+                            .to_string()
+                    ),
+                },
+                Listing {
+                    id: 42,
+                    title: Some("Zimmer mit Balkon".to_string()),
+                    rent: Some(1_450),
+                    size: None,
+                    url: Some("https://example.com/42".to_string()),
+                },
+                Listing {
+                    id: 33_333_333,
+                    title: None,
+                    rent: None,
+                    size: None,
+                    url: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn default_strategies_read_the_data_attributes() {
+        let html = Html::parse_document(
+            r#"<a href="/logout">Logout</a><a data-csrf_token="abc" data-user_id="42">Offer</a>"#,
+        );
+        assert_eq!(
+            html.scrape_csrf_token_and_user_id(&Scraping::default())
+                .expect("scraped session data"),
+            ("abc".to_string(), "42".to_string())
+        );
+    }
+
+    #[test]
+    fn default_strategies_fall_back_to_meta_tags_and_scripts() {
+        let html = Html::parse_document(
+            r#"<head><meta name="csrf-token" content="abc"></head><script>user_id = 42;</script>"#,
+        );
+        assert_eq!(
+            html.scrape_csrf_token_and_user_id(&Scraping::default())
+                .expect("scraped session data"),
+            ("abc".to_string(), "42".to_string())
+        );
+    }
+
+    #[test]
+    fn missing_values_list_the_tried_strategies() {
+        let html = Html::parse_document(r#"<a data-csrf_token="abc">Offer</a>"#);
+        assert_eq!(
+            html.scrape_csrf_token_and_user_id(&Scraping::default())
+                .expect_err("user ID is missing")
+                .to_string(),
+            r#"Could not find user ID, tried: attribute "data-user_id" of CSS selector "a[data-user_id]", script variable "user_id""#
+        );
     }
 }
@@ -0,0 +1,200 @@
+use std::fs::read;
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use serde_json::{Map, Value};
+
+use crate::diff::value_to_string;
+
+/// Maximum size of an image to upload in bytes.
+pub const MAX_SIZE: usize = 10 * 1024 * 1024;
+
+/// An image attached to an offer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Image {
+    pub(crate) id: String,
+    pub(crate) url: Option<String>,
+}
+
+impl TryFrom<Map<String, Value>> for Image {
+    type Error = anyhow::Error;
+
+    fn try_from(map: Map<String, Value>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: map
+                .get("image_id")
+                .and_then(value_to_string)
+                .ok_or_else(|| anyhow!("Image lacks an ID"))?,
+            url: map.get("sized_image").and_then(value_to_string),
+        })
+    }
+}
+
+/// Supported image formats.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Format {
+    /// JPEG image.
+    Jpeg,
+    /// PNG image.
+    Png,
+}
+
+impl Format {
+    /// Detect the format by the file's magic bytes.
+    #[must_use]
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(Self::Jpeg)
+        } else if bytes.starts_with(&[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n']) {
+            Some(Self::Png)
+        } else {
+            None
+        }
+    }
+
+    /// Return the MIME type of the format.
+    #[must_use]
+    pub const fn mime_type(self) -> &'static str {
+        match self {
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+        }
+    }
+}
+
+/// A local image file that has been checked for upload.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LocalImage {
+    pub(crate) path: PathBuf,
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) format: Format,
+}
+
+impl LocalImage {
+    /// Read an image file and check its size and format.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] if the file could not be read,
+    /// is too large or is not a supported image format.
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let bytes = read(path)?;
+
+        if bytes.len() > MAX_SIZE {
+            return Err(anyhow!(
+                "{} is too large: {} bytes (max. {MAX_SIZE} bytes)",
+                path.display(),
+                bytes.len()
+            ));
+        }
+
+        let format = Format::detect(&bytes)
+            .ok_or_else(|| anyhow!("{} is neither a JPEG nor a PNG image", path.display()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            bytes,
+            format,
+        })
+    }
+
+    /// Return a fingerprint of the image's content.
+    ///
+    /// This is the 64 bit FNV-1a hash of the image's bytes, which is stable across runs.
+    #[must_use]
+    pub fn fingerprint(&self) -> String {
+        format!(
+            "{:016x}",
+            self.bytes
+                .iter()
+                .fold(0xcbf2_9ce4_8422_2325_u64, |hash, &byte| {
+                    (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+                })
+        )
+    }
+
+    /// Return the file name of the image.
+    #[must_use]
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{remove_file, write};
+    use std::path::PathBuf;
+
+    use super::{Format, LocalImage, MAX_SIZE};
+
+    const JPEG: [u8; 4] = [0xFF, 0xD8, 0xFF, 0xE0];
+    const PNG: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+    fn temp_file(name: &str, bytes: &[u8]) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("wg_gesucht_updater-{}-{name}", std::process::id()));
+        write(&path, bytes).expect("write temporary file");
+        path
+    }
+
+    #[test]
+    fn detect() {
+        assert_eq!(Format::detect(&JPEG), Some(Format::Jpeg));
+        assert_eq!(Format::detect(&PNG), Some(Format::Png));
+        assert_eq!(Format::detect(b"GIF89a"), None);
+        assert_eq!(Format::detect(&PNG[..4]), None);
+        assert_eq!(Format::detect(&[]), None);
+    }
+
+    #[test]
+    fn fingerprint() {
+        let image = |bytes: &[u8]| LocalImage {
+            path: PathBuf::from("image.jpg"),
+            bytes: bytes.to_vec(),
+            format: Format::Jpeg,
+        };
+        assert_eq!(image(b"").fingerprint(), "cbf29ce484222325");
+        assert_eq!(image(b"a").fingerprint(), "af63dc4c8601ec8c");
+        assert_eq!(image(b"foobar").fingerprint(), "85944171f73967e8");
+    }
+
+    #[test]
+    fn size_limit() {
+        let mut bytes = JPEG.to_vec();
+        bytes.resize(MAX_SIZE, 0);
+        let path = temp_file("max.jpg", &bytes);
+        let image = LocalImage::read(&path);
+        bytes.push(0);
+        write(&path, &bytes).expect("write temporary file");
+        let too_large = LocalImage::read(&path);
+        remove_file(&path).expect("remove temporary file");
+
+        let image = image.expect("image of maximum size");
+        assert_eq!(image.format, Format::Jpeg);
+        assert_eq!(
+            image.file_name(),
+            path.file_name().unwrap().to_string_lossy()
+        );
+        assert!(
+            too_large
+                .expect_err("too large")
+                .to_string()
+                .contains("too large")
+        );
+    }
+
+    #[test]
+    fn unsupported_format() {
+        let path = temp_file("image.gif", b"GIF89a");
+        let result = LocalImage::read(&path);
+        remove_file(&path).expect("remove temporary file");
+        assert!(
+            result
+                .expect_err("unsupported format")
+                .to_string()
+                .contains("neither a JPEG nor a PNG image")
+        );
+    }
+}
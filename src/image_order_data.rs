@@ -0,0 +1,18 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct ImageOrderData<'data> {
+    image_order: &'data [String],
+    title_image_id: Option<&'data str>,
+    csrf_token: &'data str,
+}
+
+impl<'data> ImageOrderData<'data> {
+    pub fn new(image_order: &'data [String], csrf_token: &'data str) -> Self {
+        Self {
+            image_order,
+            title_image_id: image_order.first().map(String::as_str),
+            csrf_token,
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+/// An offer as listed in the public search results.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Listing {
+    pub(crate) id: u32,
+    pub(crate) title: Option<String>,
+    pub(crate) rent: Option<u32>,
+    pub(crate) size: Option<u32>,
+    pub(crate) url: Option<String>,
+}
+
+impl Display for Listing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.id)?;
+
+        if let Some(title) = &self.title {
+            write!(f, " {title}")?;
+        }
+
+        if let Some(rent) = self.rent {
+            write!(f, ", {rent} €")?;
+        }
+
+        if let Some(size) = self.size {
+            write!(f, ", {size} m²")?;
+        }
+
+        if let Some(url) = &self.url {
+            write!(f, ", {url}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,28 +1,146 @@
 //! CLI tool to programmatically update offers on wg-gesucht.de.
 
-use args::Args;
+use std::time::Duration;
+
+use args::{Args, Mode};
+use env_logger::{Target, WriteStyle};
+use log::LevelFilter;
 use settings::Settings;
 
 mod args;
 mod auth_data;
+mod backup;
+mod catalog;
 mod client;
+mod commands;
 mod config_file;
+mod conversation;
+mod credentials;
+mod csrf_data;
+mod diff;
+mod edit_data;
 mod error;
 mod functions;
 mod html_ext;
+mod image;
+mod image_order_data;
+mod listing;
 mod login_data;
+mod message_data;
+mod metrics;
+mod notifier;
+mod offer;
+mod offer_ref;
+mod offer_stats;
+mod offer_update;
 mod patch_data;
+mod reconcile;
 mod response_ext;
+mod server;
 mod settings;
+mod state;
+mod tag_expression;
+mod template;
+mod verification_data;
+mod viewing;
 
 use clap::Parser;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    env_logger::builder().format_timestamp(None).init();
+    let mode = Args::parse().mode;
+    let mut logger = env_logger::builder();
+    logger.format_timestamp(None);
+
+    if matches!(mode, Mode::Tui { .. }) {
+        logger
+            .filter_level(LevelFilter::Info)
+            .parse_default_env()
+            .write_style(WriteStyle::Never)
+            .target(Target::Pipe(Box::new(commands::tui::LogBuffer)));
+    }
+
+    logger.init();
 
-    for setting in Vec::<Settings>::try_from(Args::parse())? {
-        setting.apply().await?;
+    match mode {
+        Mode::Cli(parameters) => Settings::try_from(parameters)?.apply().await?,
+        Mode::ConfigFile { config_file } => {
+            for setting in Settings::load(config_file)? {
+                setting.apply().await?;
+            }
+        }
+        Mode::Init { config_file } => commands::init::run(&config_file).await?,
+        Mode::Plan { config_file } => {
+            for setting in Settings::load(config_file)? {
+                println!("{}:\n{}", setting.user_name(), setting.plan().await?);
+            }
+        }
+        Mode::Serve {
+            config_file,
+            listen,
+        } => server::serve(&config_file, listen).await?,
+        Mode::Edit(args) => commands::edit::run(args).await?,
+        Mode::Export {
+            config_file,
+            output,
+            account,
+            offers,
+        } => commands::export::run(&config_file, account.as_deref(), &offers, &output).await?,
+        Mode::Import(args) => commands::import::run(args).await?,
+        Mode::Applicants {
+            config_file,
+            output,
+            account,
+            offers,
+        } => commands::applicants::run(&config_file, account.as_deref(), &offers, &output).await?,
+        Mode::Viewings { viewings, output } => commands::viewings::run(&viewings, &output)?,
+        Mode::AutoReply {
+            config_file,
+            account,
+            dry_run,
+        } => commands::auto_reply::run(&config_file, account.as_deref(), dry_run).await?,
+        Mode::Messages {
+            config_file,
+            account,
+            unread,
+        } => commands::messages::run(&config_file, account.as_deref(), unread).await?,
+        Mode::Diagnose { config_file, page } => commands::diagnose::run(&config_file, &page)?,
+        Mode::Watch {
+            config_file,
+            interval_min,
+            once,
+        } => {
+            commands::watch::run(
+                &config_file,
+                (!once).then(|| Duration::from_secs(interval_min * 60)),
+            )
+            .await?;
+        }
+        Mode::Rank {
+            config_file,
+            account,
+            dry_run,
+        } => commands::rank::run(&config_file, account.as_deref(), dry_run).await?,
+        Mode::Stats {
+            config_file,
+            account,
+            record,
+            history,
+        } => commands::stats::run(&config_file, account.as_deref(), record, history).await?,
+        Mode::Photos {
+            config_file,
+            account,
+            dry_run,
+            yes,
+            prune,
+        } => commands::photos::run(&config_file, account.as_deref(), dry_run, yes, prune).await?,
+        Mode::SyncTexts {
+            config_file,
+            account,
+            dry_run,
+            yes,
+        } => commands::sync_texts::run(&config_file, account.as_deref(), dry_run, yes).await?,
+        Mode::Tui { config_file } => commands::tui::run(&config_file).await?,
     }
 
     Ok(())
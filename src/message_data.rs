@@ -0,0 +1,16 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct MessageData<'data> {
+    content: &'data str,
+    csrf_token: &'data str,
+}
+
+impl<'data> MessageData<'data> {
+    pub const fn new(content: &'data str, csrf_token: &'data str) -> Self {
+        Self {
+            content,
+            csrf_token,
+        }
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs::{rename, write};
+use std::path::Path;
+use std::sync::{LazyLock, Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use reqwest::Response;
+use serde::{Deserialize, Serialize};
+
+use crate::state::PersistentState;
+
+const PREFIX: &str = "wg_gesucht";
+const BUCKETS: [f64; 9] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+static METRICS: LazyLock<Mutex<Metrics>> = LazyLock::new(Mutex::default);
+
+/// Collected metrics in the Prometheus exposition format.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Metrics {
+    login_attempts: BTreeMap<String, u64>,
+    login_failures: BTreeMap<String, u64>,
+    patch_requests: BTreeMap<(&'static str, String), u64>,
+    request_durations: BTreeMap<&'static str, Histogram>,
+    last_bump: BTreeMap<(String, u32), DateTime<Utc>>,
+    offer_active: BTreeMap<(String, u32), bool>,
+}
+
+/// Times of the last successful bumps per account and offer.
+///
+/// They are persisted, so that one-shot runs keep reporting bumps of previous runs.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+struct LastBumps(BTreeMap<String, BTreeMap<u32, DateTime<Utc>>>);
+
+impl PersistentState for LastBumps {
+    const FILE_NAME: &'static str = "last_bumps.json";
+}
+
+/// Label value escaped for the exposition format.
+struct Label<'value>(&'value str);
+
+impl Display for Label<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for char in self.0.chars() {
+            match char {
+                '\\' => write!(f, "\\\\")?,
+                '"' => write!(f, "\\\"")?,
+                '\n' => write!(f, "\\n")?,
+                char => write!(f, "{char}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Histogram {
+    buckets: [u64; BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bucket, &bound) in self.buckets.iter_mut().zip(&BUCKETS) {
+            if value <= bound {
+                *bucket += 1;
+            }
+        }
+
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+/// Return a snapshot of the current metrics.
+#[must_use]
+pub fn snapshot() -> Metrics {
+    lock().clone()
+}
+
+/// Record a login attempt of an account.
+pub fn record_login(account: &str, success: bool) {
+    let mut metrics = lock();
+    *metrics
+        .login_attempts
+        .entry(account.to_string())
+        .or_default() += 1;
+
+    if !success {
+        *metrics
+            .login_failures
+            .entry(account.to_string())
+            .or_default() += 1;
+    }
+}
+
+/// Record a PATCH request with the respective action.
+pub fn record_patch(action: &'static str, result: &anyhow::Result<Response>) {
+    let status = match result {
+        Ok(response) => response.status().as_str().to_string(),
+        Err(error) => error
+            .downcast_ref::<reqwest::Error>()
+            .and_then(reqwest::Error::status)
+            .map_or_else(|| "error".to_string(), |status| status.as_str().to_string()),
+    };
+    *lock().patch_requests.entry((action, status)).or_default() += 1;
+}
+
+/// Record the time of a successful bump of an offer.
+pub fn record_bump(account: &str, offer: u32) {
+    lock()
+        .last_bump
+        .insert((account.to_string(), offer), Utc::now());
+}
+
+/// Record the activation state of an offer.
+pub fn record_state(account: &str, offer: u32, active: bool) {
+    lock()
+        .offer_active
+        .insert((account.to_string(), offer), active);
+}
+
+/// Measure the duration of a request to the given endpoint.
+pub async fn observe<F>(endpoint: &'static str, future: F) -> F::Output
+where
+    F: Future,
+{
+    let start = Instant::now();
+    let output = future.await;
+    lock()
+        .request_durations
+        .entry(endpoint)
+        .or_default()
+        .observe(start.elapsed().as_secs_f64());
+    output
+}
+
+/// Write the current metrics to a file for the node exporter's textfile collector.
+///
+/// The file is written atomically, so that the collector never reads partial metrics.
+/// The times of the last bumps are merged with those persisted in the state directory.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the file or the state could not be written.
+pub fn write_textfile(path: &Path, state_dir: &Path) -> anyhow::Result<()> {
+    let mut last_bumps = LastBumps::load(state_dir);
+    let mut metrics = lock();
+
+    for ((account, offer), &timestamp) in &metrics.last_bump {
+        let persisted = last_bumps
+            .0
+            .entry(account.clone())
+            .or_default()
+            .entry(*offer)
+            .or_insert(timestamp);
+        *persisted = timestamp.max(*persisted);
+    }
+
+    for (account, offers) in &last_bumps.0 {
+        for (&offer, &timestamp) in offers {
+            metrics
+                .last_bump
+                .insert((account.clone(), offer), timestamp);
+        }
+    }
+
+    let snapshot = metrics.clone();
+    drop(metrics);
+    last_bumps.save(state_dir)?;
+    let tmp = path.with_extension("prom.tmp");
+    write(&tmp, snapshot.to_string())?;
+    Ok(rename(tmp, path)?)
+}
+
+fn lock() -> MutexGuard<'static, Metrics> {
+    METRICS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Display for Metrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "# HELP {PREFIX}_login_attempts_total Login attempts per account."
+        )?;
+        writeln!(f, "# TYPE {PREFIX}_login_attempts_total counter")?;
+        for (account, count) in &self.login_attempts {
+            writeln!(
+                f,
+                "{PREFIX}_login_attempts_total{{account=\"{}\"}} {count}",
+                Label(account)
+            )?;
+        }
+
+        writeln!(
+            f,
+            "# HELP {PREFIX}_login_failures_total Failed logins per account."
+        )?;
+        writeln!(f, "# TYPE {PREFIX}_login_failures_total counter")?;
+        for (account, count) in &self.login_failures {
+            writeln!(
+                f,
+                "{PREFIX}_login_failures_total{{account=\"{}\"}} {count}",
+                Label(account)
+            )?;
+        }
+
+        writeln!(
+            f,
+            "# HELP {PREFIX}_patch_requests_total PATCH requests by action and status code."
+        )?;
+        writeln!(f, "# TYPE {PREFIX}_patch_requests_total counter")?;
+        for ((action, status), count) in &self.patch_requests {
+            writeln!(
+                f,
+                "{PREFIX}_patch_requests_total{{action=\"{action}\",status=\"{}\"}} {count}",
+                Label(status)
+            )?;
+        }
+
+        writeln!(
+            f,
+            "# HELP {PREFIX}_request_duration_seconds Request latencies by endpoint."
+        )?;
+        writeln!(f, "# TYPE {PREFIX}_request_duration_seconds histogram")?;
+        for (endpoint, histogram) in &self.request_durations {
+            for (bound, count) in BUCKETS.iter().zip(histogram.buckets) {
+                writeln!(
+                    f,
+                    "{PREFIX}_request_duration_seconds_bucket{{endpoint=\"{endpoint}\",le=\"{bound}\"}} {count}"
+                )?;
+            }
+            writeln!(
+                f,
+                "{PREFIX}_request_duration_seconds_bucket{{endpoint=\"{endpoint}\",le=\"+Inf\"}} {}",
+                histogram.count
+            )?;
+            writeln!(
+                f,
+                "{PREFIX}_request_duration_seconds_sum{{endpoint=\"{endpoint}\"}} {}",
+                histogram.sum
+            )?;
+            writeln!(
+                f,
+                "{PREFIX}_request_duration_seconds_count{{endpoint=\"{endpoint}\"}} {}",
+                histogram.count
+            )?;
+        }
+
+        writeln!(
+            f,
+            "# HELP {PREFIX}_last_bump_timestamp_seconds Time of the last successful bump per offer."
+        )?;
+        writeln!(f, "# TYPE {PREFIX}_last_bump_timestamp_seconds gauge")?;
+        for ((account, offer), timestamp) in &self.last_bump {
+            writeln!(
+                f,
+                "{PREFIX}_last_bump_timestamp_seconds{{account=\"{}\",offer=\"{offer}\"}} {}",
+                Label(account),
+                timestamp.timestamp()
+            )?;
+        }
+
+        writeln!(
+            f,
+            "# HELP {PREFIX}_offer_active Activation state per offer."
+        )?;
+        writeln!(f, "# TYPE {PREFIX}_offer_active gauge")?;
+        for ((account, offer), active) in &self.offer_active {
+            writeln!(
+                f,
+                "{PREFIX}_offer_active{{account=\"{}\",offer=\"{offer}\"}} {}",
+                Label(account),
+                u8::from(*active)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+
+    use super::{Histogram, Label, Metrics};
+
+    #[test]
+    fn escape_label() {
+        assert_eq!(Label("a\\b\"c\nd").to_string(), r#"a\\b\"c\nd"#);
+    }
+
+    #[test]
+    fn format() {
+        let mut histogram = Histogram::default();
+        histogram.observe(0.3);
+        let timestamp = DateTime::from_timestamp(1_700_000_000, 0).expect("valid timestamp");
+        let metrics = Metrics {
+            login_attempts: [("a\"b".to_string(), 2)].into(),
+            login_failures: [("a\"b".to_string(), 1)].into(),
+            patch_requests: [(("bump", "200".to_string()), 3)].into(),
+            request_durations: [("offers", histogram)].into(),
+            last_bump: [(("x\ny".to_string(), 42), timestamp)].into(),
+            offer_active: [(("x\ny".to_string(), 42), true)].into(),
+        };
+        let text = metrics.to_string();
+
+        for line in [
+            r#"wg_gesucht_login_attempts_total{account="a\"b"} 2"#,
+            r#"wg_gesucht_login_failures_total{account="a\"b"} 1"#,
+            r#"wg_gesucht_patch_requests_total{action="bump",status="200"} 3"#,
+            r#"wg_gesucht_request_duration_seconds_bucket{endpoint="offers",le="0.25"} 0"#,
+            r#"wg_gesucht_request_duration_seconds_bucket{endpoint="offers",le="0.5"} 1"#,
+            r#"wg_gesucht_request_duration_seconds_bucket{endpoint="offers",le="+Inf"} 1"#,
+            r#"wg_gesucht_request_duration_seconds_sum{endpoint="offers"} 0.3"#,
+            r#"wg_gesucht_request_duration_seconds_count{endpoint="offers"} 1"#,
+            r#"wg_gesucht_last_bump_timestamp_seconds{account="x\ny",offer="42"} 1700000000"#,
+            r#"wg_gesucht_offer_active{account="x\ny",offer="42"} 1"#,
+        ] {
+            assert!(text.lines().any(|other| other == line), "{line} in {text}");
+        }
+
+        assert!(
+            text.lines()
+                .all(|line| line.starts_with("# ") || line.starts_with("wg_gesucht_"))
+        );
+    }
+}
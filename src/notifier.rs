@@ -0,0 +1,114 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use log::error;
+pub use notification::{Kind, Notification};
+use reqwest::Client;
+pub use smtp::Smtp;
+pub use webhook::Webhook;
+
+mod notification;
+mod smtp;
+mod webhook;
+
+use crate::client::{TIMEOUT, USER_AGENT};
+
+/// Dispatches notifications to all configured receivers.
+///
+/// Webhooks are called with the given user agent and timeout,
+/// unless a webhook configures its own timeout.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Notifier {
+    webhooks: Vec<Webhook>,
+    smtp: Option<Smtp>,
+    state_dir: PathBuf,
+    user_agent: String,
+    timeout: Duration,
+}
+
+impl Notifier {
+    /// Create a new notifier.
+    #[must_use]
+    pub const fn new(
+        webhooks: Vec<Webhook>,
+        smtp: Option<Smtp>,
+        state_dir: PathBuf,
+        user_agent: String,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            webhooks,
+            smtp,
+            state_dir,
+            user_agent,
+            timeout,
+        }
+    }
+
+    /// Return `true` iff there are no webhooks.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.webhooks.is_empty()
+    }
+
+    /// Return the directory to persist state in.
+    #[must_use]
+    pub fn state_dir(&self) -> &Path {
+        &self.state_dir
+    }
+
+    /// Track the outcome of a run and send email alerts to the given recipients if necessary.
+    pub async fn alert(&self, outcome: &Notification<'_>, recipients: &[String]) {
+        if let Some(smtp) = &self.smtp
+            && !recipients.is_empty()
+        {
+            smtp.alert(outcome, recipients, &self.state_dir).await;
+        }
+    }
+
+    /// Send a notification to all receivers that subscribed to its kind.
+    ///
+    /// Errors are logged but not propagated,
+    /// since a failing receiver shall not abort the run.
+    pub async fn notify(&self, notification: &Notification<'_>) {
+        let webhooks: Vec<&Webhook> = self
+            .webhooks
+            .iter()
+            .filter(|webhook| webhook.subscribes(notification.kind))
+            .collect();
+
+        if webhooks.is_empty() {
+            return;
+        }
+
+        let client = match Client::builder()
+            .user_agent(&self.user_agent)
+            .timeout(self.timeout)
+            .build()
+        {
+            Ok(client) => client,
+            Err(error) => {
+                error!("Could not create the webhook client: {error}");
+                return;
+            }
+        };
+
+        for webhook in webhooks {
+            if let Err(error) = webhook.send(&client, notification).await {
+                error!("Could not send notification to webhook: {error}");
+            }
+        }
+    }
+}
+
+impl Default for Notifier {
+    fn default() -> Self {
+        Self::new(
+            Vec::new(),
+            None,
+            PathBuf::new(),
+            USER_AGENT.to_string(),
+            TIMEOUT,
+        )
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt::{Display, Formatter};
+
+use serde::Serialize;
+
+/// Kinds of events a notification can be sent for.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    /// The login failed.
+    LoginFailed,
+    /// Some offers could not be updated.
+    UpdatesFailed,
+    /// Some offers have been left deactivated.
+    OffersDeactivated,
+    /// A new listing matched a watched search.
+    NewListing,
+    /// The run succeeded.
+    Success,
+}
+
+impl Kind {
+    /// Return `true` iff the kind reports a problem.
+    #[must_use]
+    pub const fn is_failure(self) -> bool {
+        !matches!(self, Self::Success | Self::NewListing)
+    }
+}
+
+/// A notification about an event on an account.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Notification<'account> {
+    #[serde(rename = "event")]
+    pub(crate) kind: Kind,
+    pub(crate) account: &'account str,
+    pub(crate) message: String,
+    pub(crate) offers: Vec<u32>,
+}
+
+impl<'account> Notification<'account> {
+    /// Create a new notification.
+    #[must_use]
+    pub const fn new(
+        kind: Kind,
+        account: &'account str,
+        message: String,
+        offers: Vec<u32>,
+    ) -> Self {
+        Self {
+            kind,
+            account,
+            message,
+            offers,
+        }
+    }
+
+    /// Return a short title of the notification.
+    #[must_use]
+    pub fn title(&self) -> String {
+        match self.kind {
+            Kind::LoginFailed => format!("Login failed for {}", self.account),
+            Kind::UpdatesFailed => format!("Offer updates failed for {}", self.account),
+            Kind::OffersDeactivated => format!("Offers left deactivated for {}", self.account),
+            Kind::NewListing => format!("New listing for {}", self.account),
+            Kind::Success => format!("Offers updated for {}", self.account),
+        }
+    }
+}
+
+impl Display for Notification<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.title(), self.message)
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use log::{debug, error, info};
+use serde::{Deserialize, Serialize};
+
+use super::Notification;
+use crate::state::PersistentState;
+
+const DEFAULT_THRESHOLD: u32 = 1;
+const DEFAULT_REPEAT_AFTER_HOURS: u32 = 24;
+
+/// SMTP server to send email alerts with.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct Smtp {
+    host: String,
+    port: Option<u16>,
+    #[serde(default)]
+    encryption: Encryption,
+    user_name: Option<String>,
+    password: Option<String>,
+    from: String,
+    threshold: Option<u32>,
+    repeat_after_hours: Option<u32>,
+}
+
+/// Connection encryption for SMTP.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Encryption {
+    /// Upgrade the connection via `STARTTLS`.
+    #[default]
+    Starttls,
+    /// Use implicit TLS.
+    Tls,
+    /// Send unencrypted. Only use this for local relays.
+    None,
+}
+
+/// Per-account alert state.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct AlertState {
+    consecutive_failures: u32,
+    last_alert: Option<DateTime<Utc>>,
+}
+
+/// Alert states of all accounts by user name.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct AlertStates(BTreeMap<String, AlertState>);
+
+impl PersistentState for AlertStates {
+    const FILE_NAME: &'static str = "email_alerts.json";
+}
+
+impl Smtp {
+    /// Track the outcome of a run and send alert or recovery emails as needed.
+    ///
+    /// An alert is sent once an account failed for more than `threshold` consecutive runs,
+    /// i.e. on the second failure in a row by default, and is repeated at most every
+    /// `repeat_after_hours`.
+    /// Once a run succeeds again after an alert, a recovery email is sent.
+    pub async fn alert(
+        &self,
+        notification: &Notification<'_>,
+        recipients: &[String],
+        state_dir: &Path,
+    ) {
+        let mut states = AlertStates::load(state_dir);
+        let state = states
+            .0
+            .entry(notification.account.to_string())
+            .or_default();
+        let now = Utc::now();
+
+        if notification.kind.is_failure() {
+            state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+
+            if state.consecutive_failures > self.threshold.unwrap_or(DEFAULT_THRESHOLD)
+                && state.last_alert.is_none_or(|last_alert| {
+                    now - last_alert
+                        >= TimeDelta::hours(i64::from(
+                            self.repeat_after_hours
+                                .unwrap_or(DEFAULT_REPEAT_AFTER_HOURS),
+                        ))
+                })
+            {
+                let subject = format!(
+                    "{} ({} consecutive failed runs)",
+                    notification.title(),
+                    state.consecutive_failures
+                );
+                if self.send(recipients, &subject, &notification.message).await {
+                    state.last_alert = Some(now);
+                }
+            } else {
+                debug!("Suppressing email alert for {}", notification.account);
+            }
+        } else {
+            if state.last_alert.is_some() {
+                let subject = format!("Recovered: {}", notification.title());
+                self.send(recipients, &subject, &notification.message).await;
+            }
+
+            *state = AlertState::default();
+        }
+
+        if let Err(error) = states.save(state_dir) {
+            error!("Could not save email alert state: {error}");
+        }
+    }
+
+    async fn send(&self, recipients: &[String], subject: &str, body: &str) -> bool {
+        match self.try_send(recipients, subject, body).await {
+            Ok(()) => {
+                info!("Sent email alert to {}", recipients.join(", "));
+                true
+            }
+            Err(error) => {
+                error!("Could not send email alert: {error}");
+                false
+            }
+        }
+    }
+
+    async fn try_send(
+        &self,
+        recipients: &[String],
+        subject: &str,
+        body: &str,
+    ) -> anyhow::Result<()> {
+        let mut message = Message::builder().from(self.from.parse::<Mailbox>()?);
+
+        for recipient in recipients {
+            message = message.to(recipient.parse()?);
+        }
+
+        self.transport()?
+            .send(message.subject(subject).body(body.to_string())?)
+            .await?;
+        Ok(())
+    }
+
+    fn transport(&self) -> anyhow::Result<AsyncSmtpTransport<Tokio1Executor>> {
+        let mut builder = match self.encryption {
+            Encryption::Starttls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.host)?
+            }
+            Encryption::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&self.host)?,
+            Encryption::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&self.host),
+        };
+
+        if let Some(port) = self.port {
+            builder = builder.port(port);
+        }
+
+        if let (Some(user_name), Some(password)) = (&self.user_name, &self.password) {
+            builder = builder.credentials(Credentials::new(user_name.clone(), password.clone()));
+        }
+
+        Ok(builder.build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex, PoisonError};
+    use std::thread;
+
+    use chrono::{TimeDelta, Utc};
+
+    use super::{AlertStates, Encryption, Smtp};
+    use crate::notifier::{Kind, Notification};
+    use crate::state::PersistentState;
+
+    const ACCOUNT: &str = "user";
+
+    type Subjects = Arc<Mutex<Vec<String>>>;
+
+    /// Accept SMTP sessions and record the subjects of the received messages.
+    fn sink() -> (u16, Subjects) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let port = listener.local_addr().expect("address").port();
+        let subjects = Subjects::default();
+        let received = subjects.clone();
+
+        thread::spawn(move || {
+            for stream in listener.incoming().map_while(Result::ok) {
+                let mut writer = stream.try_clone().expect("clone stream");
+                let mut lines = BufReader::new(stream).lines().map_while(Result::ok);
+                writer.write_all(b"220 sink\r\n").expect("greet");
+
+                while let Some(line) = lines.next() {
+                    let reply: &[u8] = match line.get(..4).unwrap_or_default() {
+                        "EHLO" | "HELO" => b"250 sink\r\n",
+                        "DATA" => {
+                            writer.write_all(b"354 go ahead\r\n").expect("reply");
+
+                            for line in lines.by_ref().take_while(|line| line != ".") {
+                                if let Some(subject) = line.strip_prefix("Subject: ") {
+                                    received
+                                        .lock()
+                                        .unwrap_or_else(PoisonError::into_inner)
+                                        .push(subject.to_string());
+                                }
+                            }
+
+                            b"250 queued\r\n"
+                        }
+                        "QUIT" => {
+                            writer.write_all(b"221 bye\r\n").expect("reply");
+                            break;
+                        }
+                        _ => b"250 ok\r\n",
+                    };
+                    writer.write_all(reply).expect("reply");
+                }
+            }
+        });
+
+        (port, subjects)
+    }
+
+    fn state_dir(name: &str) -> PathBuf {
+        let state_dir = std::env::temp_dir().join(format!(
+            "wg_gesucht_updater-smtp-{name}-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&state_dir).expect("create state dir");
+        let _ = std::fs::remove_file(state_dir.join("email_alerts.json"));
+        state_dir
+    }
+
+    async fn run(smtp: &Smtp, kind: Kind, state_dir: &Path) {
+        smtp.alert(
+            &Notification::new(kind, ACCOUNT, "message".to_string(), Vec::new()),
+            &["elders@example.com".to_string()],
+            state_dir,
+        )
+        .await;
+    }
+
+    fn received(subjects: &Subjects) -> Vec<String> {
+        std::mem::take(&mut *subjects.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    #[tokio::test]
+    async fn alert_repeat_and_recovery() {
+        let (port, subjects) = sink();
+        let state_dir = state_dir("sequence");
+        let smtp = Smtp {
+            host: "127.0.0.1".to_string(),
+            port: Some(port),
+            encryption: Encryption::None,
+            user_name: None,
+            password: None,
+            from: "bumper@example.com".to_string(),
+            threshold: Some(2),
+            repeat_after_hours: Some(24),
+        };
+
+        run(&smtp, Kind::UpdatesFailed, &state_dir).await;
+        run(&smtp, Kind::UpdatesFailed, &state_dir).await;
+        assert!(received(&subjects).is_empty());
+
+        run(&smtp, Kind::UpdatesFailed, &state_dir).await;
+        assert_eq!(
+            received(&subjects),
+            ["Offer updates failed for user (3 consecutive failed runs)"]
+        );
+
+        run(&smtp, Kind::UpdatesFailed, &state_dir).await;
+        assert!(received(&subjects).is_empty());
+
+        let mut states = AlertStates::load(&state_dir);
+        let state = states.0.get_mut(ACCOUNT).expect("state of account");
+        state.last_alert = Some(Utc::now() - TimeDelta::hours(25));
+        states.save(&state_dir).expect("save state");
+
+        run(&smtp, Kind::LoginFailed, &state_dir).await;
+        assert_eq!(
+            received(&subjects),
+            ["Login failed for user (5 consecutive failed runs)"]
+        );
+
+        run(&smtp, Kind::Success, &state_dir).await;
+        assert_eq!(received(&subjects), ["Recovered: Offers updated for user"]);
+
+        run(&smtp, Kind::Success, &state_dir).await;
+        run(&smtp, Kind::UpdatesFailed, &state_dir).await;
+        assert!(received(&subjects).is_empty());
+    }
+}
@@ -0,0 +1,234 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::anyhow;
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+
+use super::{Kind, Notification};
+
+/// A webhook to receive notifications.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct Webhook {
+    url: String,
+    #[serde(default)]
+    kind: WebhookKind,
+    #[serde(default)]
+    on_success: bool,
+    #[serde(default)]
+    on_new_listing: bool,
+    timeout_sec: Option<u64>,
+}
+
+/// Payload formats of webhooks.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookKind {
+    /// The notification is posted as JSON object.
+    #[default]
+    Generic,
+    /// The notification is sent as text message to a Matrix room.
+    Matrix,
+    /// The notification is posted as Slack-compatible message.
+    Slack,
+    /// The notification is posted as plain text with a title header.
+    Ntfy,
+}
+
+#[derive(Serialize)]
+struct MatrixMessage<'text> {
+    msgtype: &'static str,
+    body: &'text str,
+}
+
+#[derive(Serialize)]
+struct SlackMessage<'text> {
+    text: &'text str,
+}
+
+impl Webhook {
+    /// Return `true` iff the webhook shall receive notifications of the given kind.
+    #[must_use]
+    pub const fn subscribes(&self, kind: Kind) -> bool {
+        match kind {
+            Kind::Success => self.on_success,
+            Kind::NewListing => self.on_new_listing,
+            Kind::LoginFailed | Kind::UpdatesFailed | Kind::OffersDeactivated => true,
+        }
+    }
+
+    /// Send a notification to the webhook via the given client.
+    ///
+    /// The webhook's own timeout, if any, takes precedence over the client's.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] on request errors.
+    pub async fn send(
+        &self,
+        client: &Client,
+        notification: &Notification<'_>,
+    ) -> anyhow::Result<()> {
+        let text = notification.to_string();
+        let request = match self.kind {
+            WebhookKind::Generic => client.post(&self.url).json(notification),
+            WebhookKind::Matrix => client.put(self.matrix_url()?).json(&MatrixMessage {
+                msgtype: "m.text",
+                body: &text,
+            }),
+            WebhookKind::Slack => client.post(&self.url).json(&SlackMessage { text: &text }),
+            WebhookKind::Ntfy => client
+                .post(&self.url)
+                .header("Title", notification.title())
+                .body(notification.message.clone()),
+        };
+        let request = match self.timeout_sec {
+            Some(timeout_sec) => request.timeout(Duration::from_secs(timeout_sec)),
+            None => request,
+        };
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+
+    /// Matrix requires a unique transaction ID as last path segment.
+    fn matrix_url(&self) -> anyhow::Result<Url> {
+        let mut url = Url::parse(&self.url)?;
+        url.path_segments_mut()
+            .map_err(|()| anyhow!("Matrix URL cannot be a base"))?
+            .push(
+                &SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_nanos()
+                    .to_string(),
+            );
+        Ok(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex, PoisonError};
+
+    use axum::Router;
+    use axum::extract::State;
+    use axum::http::{HeaderMap, Method, Uri};
+    use reqwest::Client;
+    use tokio::net::TcpListener;
+
+    use super::{Webhook, WebhookKind};
+    use crate::notifier::{Kind, Notification};
+
+    type Request = (Method, String, HeaderMap, String);
+    type Requests = Arc<Mutex<Vec<Request>>>;
+
+    async fn record(
+        State(requests): State<Requests>,
+        method: Method,
+        uri: Uri,
+        headers: HeaderMap,
+        body: String,
+    ) {
+        requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push((method, uri.path().to_string(), headers, body));
+    }
+
+    async fn send(kind: WebhookKind, path: &str) -> Request {
+        let requests = Requests::default();
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let address = listener.local_addr().expect("address");
+        let router = Router::new().fallback(record).with_state(requests.clone());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        Webhook {
+            url: format!("http://{address}{path}"),
+            kind,
+            on_success: false,
+            on_new_listing: false,
+            timeout_sec: None,
+        }
+        .send(
+            &Client::builder()
+                .user_agent("wg_gesucht_updater/test")
+                .build()
+                .expect("valid client"),
+            &Notification::new(
+                Kind::UpdatesFailed,
+                "user",
+                "Failed to bump #1".to_string(),
+                vec![1],
+            ),
+        )
+        .await
+        .expect("webhook accepted the notification");
+
+        let mut requests = requests.lock().unwrap_or_else(PoisonError::into_inner);
+        assert_eq!(requests.len(), 1);
+        requests.remove(0)
+    }
+
+    #[tokio::test]
+    async fn generic() {
+        let (method, path, headers, body) = send(WebhookKind::Generic, "/hook").await;
+        assert_eq!(method, Method::POST);
+        assert_eq!(path, "/hook");
+        assert_eq!(
+            headers
+                .get("user-agent")
+                .and_then(|agent| agent.to_str().ok()),
+            Some("wg_gesucht_updater/test")
+        );
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&body).expect("JSON body"),
+            serde_json::json!({
+                "event": "updates_failed",
+                "account": "user",
+                "message": "Failed to bump #1",
+                "offers": [1],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn matrix() {
+        let (method, path, _, body) = send(
+            WebhookKind::Matrix,
+            "/_matrix/client/v3/rooms/room/send/m.room.message",
+        )
+        .await;
+        assert_eq!(method, Method::PUT);
+        let transaction_id = path
+            .strip_prefix("/_matrix/client/v3/rooms/room/send/m.room.message/")
+            .expect("transaction ID appended");
+        assert!(transaction_id.chars().all(|char| char.is_ascii_digit()));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&body).expect("JSON body"),
+            serde_json::json!({
+                "msgtype": "m.text",
+                "body": "Offer updates failed for user: Failed to bump #1",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn slack() {
+        let (method, _, _, body) = send(WebhookKind::Slack, "/").await;
+        assert_eq!(method, Method::POST);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&body).expect("JSON body"),
+            serde_json::json!({ "text": "Offer updates failed for user: Failed to bump #1" })
+        );
+    }
+
+    #[tokio::test]
+    async fn ntfy() {
+        let (method, _, headers, body) = send(WebhookKind::Ntfy, "/topic").await;
+        assert_eq!(method, Method::POST);
+        assert_eq!(
+            headers.get("Title").and_then(|title| title.to_str().ok()),
+            Some("Offer updates failed for user")
+        );
+        assert_eq!(body, "Failed to bump #1");
+    }
+}
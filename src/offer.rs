@@ -0,0 +1,31 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// An offer as listed on the "Meine Anzeigen" page.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Offer {
+    pub(crate) id: u32,
+    pub(crate) title: Option<String>,
+    pub(crate) active: bool,
+    pub(crate) last_bumped: Option<DateTime<Utc>>,
+}
+
+impl Display for Offer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.id)?;
+
+        if let Some(title) = &self.title {
+            write!(f, " ({title})")?;
+        }
+
+        write!(f, ": {}", if self.active { "active" } else { "inactive" })?;
+
+        if let Some(last_bumped) = self.last_bumped {
+            write!(f, ", last bumped {}", last_bumped.format("%Y-%m-%d %H:%M"))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tag_expression::TagExpression;
+
+const GROUP_PREFIX: &str = "@";
+const TAGS_PREFIX: &str = "tag:";
+
+/// Reference to one or more offers.
+///
+/// In config files and on the command line, groups are written as `@<group>`
+/// and tag expressions as `tag:<expression>`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "RawOfferRef", into = "RawOfferRef")]
+pub enum OfferRef {
+    /// The ID of the offer.
+    Id(u32),
+    /// The name of the offer as configured in the account's offers.
+    Name(String),
+    /// The offers of a group configured in the account's groups.
+    Group(String),
+    /// The named offers whose tags match the expression.
+    Tags(TagExpression),
+}
+
+impl FromStr for OfferRef {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(group) = s.strip_prefix(GROUP_PREFIX) {
+            Ok(Self::Group(group.to_string()))
+        } else if let Some(expression) = s.strip_prefix(TAGS_PREFIX) {
+            Ok(Self::Tags(expression.parse()?))
+        } else {
+            Ok(s.parse()
+                .map_or_else(|_| Self::Name(s.to_string()), Self::Id))
+        }
+    }
+}
+
+impl Display for OfferRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Id(id) => write!(f, "#{id}"),
+            Self::Name(name) => write!(f, "{name:?}"),
+            Self::Group(group) => write!(f, "{GROUP_PREFIX}{group}"),
+            Self::Tags(expression) => write!(f, "{TAGS_PREFIX}{expression}"),
+        }
+    }
+}
+
+/// Representation of an [`OfferRef`] in config files.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum RawOfferRef {
+    Id(u32),
+    Text(String),
+}
+
+impl TryFrom<RawOfferRef> for OfferRef {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: RawOfferRef) -> Result<Self, Self::Error> {
+        match raw {
+            RawOfferRef::Id(id) => Ok(Self::Id(id)),
+            RawOfferRef::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl From<OfferRef> for RawOfferRef {
+    fn from(offer: OfferRef) -> Self {
+        match offer {
+            OfferRef::Id(id) => Self::Id(id),
+            OfferRef::Name(name) => Self::Text(name),
+            other => Self::Text(other.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OfferRef;
+    use crate::tag_expression::TagExpression;
+
+    fn parse(s: &str) -> OfferRef {
+        s.parse().expect("Reference should be valid.")
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(parse("1234567"), OfferRef::Id(1_234_567));
+        assert_eq!(parse("Zimmer 3"), OfferRef::Name("Zimmer 3".to_string()));
+        assert_eq!(parse("-1"), OfferRef::Name("-1".to_string()));
+        assert_eq!(
+            parse("4294967296"),
+            OfferRef::Name("4294967296".to_string())
+        );
+        assert_eq!(parse("@winter"), OfferRef::Group("winter".to_string()));
+        assert_eq!(
+            parse("tag:haus-a & !winter"),
+            OfferRef::Tags(TagExpression::And(
+                Box::new(TagExpression::Tag("haus-a".to_string())),
+                Box::new(TagExpression::Not(Box::new(TagExpression::Tag(
+                    "winter".to_string()
+                )))),
+            ))
+        );
+        assert!("tag:haus-a &".parse::<OfferRef>().is_err());
+    }
+
+    #[test]
+    fn config_file() {
+        let offers: Vec<OfferRef> =
+            serde_json::from_str(r#"[1234567, "1234567", "Zimmer 3", "@winter", "tag:haus-a"]"#)
+                .expect("References should be valid.");
+        assert_eq!(
+            offers,
+            [
+                OfferRef::Id(1_234_567),
+                OfferRef::Id(1_234_567),
+                OfferRef::Name("Zimmer 3".to_string()),
+                OfferRef::Group("winter".to_string()),
+                OfferRef::Tags(TagExpression::Tag("haus-a".to_string())),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&offers).expect("References should be serializable."),
+            r#"[1234567,1234567,"Zimmer 3","@winter","tag:haus-a"]"#
+        );
+    }
+}
@@ -0,0 +1,27 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+/// View and contact counts of an offer as listed on the "Meine Anzeigen" page.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct OfferStats {
+    pub(crate) id: u32,
+    pub(crate) views: Option<u64>,
+    pub(crate) contacts: Option<u64>,
+}
+
+impl Display for OfferStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}: ", self.id)?;
+
+        match self.views {
+            Some(views) => write!(f, "{views} views")?,
+            None => write!(f, "views unknown")?,
+        }
+
+        match self.contacts {
+            Some(contacts) => write!(f, ", {contacts} contacts"),
+            None => write!(f, ", contacts unknown"),
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::diff::{Change, diff};
+use crate::functions::{GERMAN_DATE_FORMAT, date_to_german_str};
+
+/// Partial update of an offer's details.
+///
+/// Fields that are `None` are left untouched.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct OfferUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) rent_costs: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) utility_costs: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) other_costs: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) bond_costs: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) property_size: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "date_to_german_str"
+    )]
+    pub(crate) available_from_date: Option<NaiveDate>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "date_to_german_str"
+    )]
+    pub(crate) available_to_date: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) freetext_property_description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) freetext_area_description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) freetext_flatshare: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) freetext_other: Option<String>,
+}
+
+impl OfferUpdate {
+    /// Return the changes this update would apply to an offer with the given details.
+    #[must_use]
+    pub fn diff(&self, current: &Map<String, Value>) -> Vec<Change> {
+        diff(current, self.fields())
+    }
+
+    /// Return `true` iff the update does not change any field.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.fields().is_empty()
+    }
+
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        let dates = [
+            ("available_from_date", self.available_from_date),
+            ("available_to_date", self.available_to_date),
+        ];
+        [
+            ("rent_costs", self.rent_costs),
+            ("utility_costs", self.utility_costs),
+            ("other_costs", self.other_costs),
+            ("bond_costs", self.bond_costs),
+            ("property_size", self.property_size),
+        ]
+        .into_iter()
+        .filter_map(|(field, value)| value.map(|value| (field, value.to_string())))
+        .chain(dates.into_iter().filter_map(|(field, date)| {
+            date.map(|date| (field, date.format(GERMAN_DATE_FORMAT).to_string()))
+        }))
+        .chain(
+            [
+                (
+                    "freetext_property_description",
+                    &self.freetext_property_description,
+                ),
+                ("freetext_area_description", &self.freetext_area_description),
+                ("freetext_flatshare", &self.freetext_flatshare),
+                ("freetext_other", &self.freetext_other),
+            ]
+            .into_iter()
+            .filter_map(|(field, text)| text.clone().map(|text| (field, text))),
+        )
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use serde_json::{Map, Value, json};
+
+    use super::OfferUpdate;
+
+    fn update() -> OfferUpdate {
+        OfferUpdate {
+            rent_costs: Some(480),
+            available_from_date: NaiveDate::from_ymd_opt(2026, 4, 1),
+            freetext_property_description: Some("Bright room\nwith balcony".to_string()),
+            ..OfferUpdate::default()
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        assert_eq!(
+            serde_json::to_value(update()).expect("serializable update"),
+            json!({
+                "rent_costs": 480,
+                "available_from_date": "01.04.2026",
+                "freetext_property_description": "Bright room\nwith balcony",
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(OfferUpdate::default()).expect("serializable update"),
+            json!({})
+        );
+    }
+
+    #[test]
+    fn diff() {
+        let current: Map<String, Value> = serde_json::from_value(json!({
+            "rent_costs": "480",
+            "available_from_date": "15.03.2026",
+            "freetext_property_description": null,
+        }))
+        .expect("details object");
+        let changes: Vec<String> = update()
+            .diff(&current)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                "available_from_date:\n- 15.03.2026\n+ 01.04.2026\n",
+                "freetext_property_description:\n+ Bright room\n+ with balcony\n",
+            ]
+        );
+        assert!(OfferUpdate::default().is_empty());
+        assert!(!update().is_empty());
+    }
+}
@@ -0,0 +1,304 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use log::warn;
+use serde::Deserialize;
+
+use crate::config_file::{DesiredOffer, DesiredState};
+use crate::offer::Offer;
+
+/// Operations that can be performed on an offer.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    /// Activate the offer.
+    Activate,
+    /// Deactivate the offer.
+    Deactivate,
+    /// Bump the offer to newest.
+    Bump,
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Activate => write!(f, "activate"),
+            Self::Deactivate => write!(f, "deactivate"),
+            Self::Bump => write!(f, "bump"),
+        }
+    }
+}
+
+/// Reconciliation step for a single offer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Step {
+    id: u32,
+    current: Option<Offer>,
+    desired: DesiredState,
+    operation: Option<Operation>,
+}
+
+impl Step {
+    /// Plan the operation for a single offer.
+    ///
+    /// Offers that are not on the offers page, or whose last bump is unknown when they should be
+    /// fresh, are skipped with a warning rather than acted on blindly.
+    fn new(desired: DesiredOffer, current: Option<Offer>, now: DateTime<Utc>) -> Self {
+        let operation = match (desired.state, current.as_ref()) {
+            (_, None) => {
+                warn!(
+                    "Offer #{} is not on the offers page, skipping it",
+                    desired.id
+                );
+                None
+            }
+            (DesiredState::Active, Some(offer)) => (!offer.active).then_some(Operation::Activate),
+            (DesiredState::Inactive, Some(offer)) => offer.active.then_some(Operation::Deactivate),
+            (DesiredState::Fresh { .. }, Some(offer)) if !offer.active => Some(Operation::Activate),
+            (DesiredState::Fresh { .. }, Some(offer)) if offer.last_bumped.is_none() => {
+                warn!(
+                    "Last bump of offer #{} is unknown, not bumping it",
+                    desired.id
+                );
+                None
+            }
+            (DesiredState::Fresh { max_age_min }, Some(offer)) => offer
+                .last_bumped
+                .is_some_and(|last_bumped| {
+                    now - last_bumped
+                        > TimeDelta::minutes(i64::try_from(max_age_min).unwrap_or(i64::MAX))
+                })
+                .then_some(Operation::Bump),
+        };
+
+        Self {
+            id: desired.id,
+            current,
+            desired: desired.state,
+            operation,
+        }
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.current {
+            Some(offer) => write!(f, "{offer}")?,
+            None => write!(f, "#{}: state unknown", self.id)?,
+        }
+
+        match self.desired {
+            DesiredState::Active => write!(f, " => active")?,
+            DesiredState::Inactive => write!(f, " => inactive")?,
+            DesiredState::Fresh { max_age_min } => {
+                write!(f, " => active, bumped within {max_age_min} min")?;
+            }
+        }
+
+        match (self.operation, &self.current) {
+            (Some(operation), _) => write!(f, ": {operation}"),
+            (None, None) => write!(f, ": skipped"),
+            (None, Some(offer))
+                if offer.active
+                    && offer.last_bumped.is_none()
+                    && matches!(self.desired, DesiredState::Fresh { .. }) =>
+            {
+                write!(f, ": skipped, last bump unknown")
+            }
+            (None, Some(_)) => write!(f, ": up to date"),
+        }
+    }
+}
+
+/// Minimal set of operations to reach the desired state of the offers.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Plan {
+    steps: Vec<Step>,
+}
+
+impl Plan {
+    /// Create a plan to transition the current offers into the desired state.
+    #[must_use]
+    pub fn new(desired: &[DesiredOffer], current: &[Offer], now: DateTime<Utc>) -> Self {
+        if current.is_empty() && !desired.is_empty() {
+            warn!("No offers were found on the offers page, the page layout may have changed");
+        }
+
+        Self {
+            steps: desired
+                .iter()
+                .map(|&desired| {
+                    Step::new(
+                        desired,
+                        current.iter().find(|offer| offer.id == desired.id).cloned(),
+                        now,
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Return the operations that need to be performed.
+    pub fn operations(&self) -> impl Iterator<Item = (u32, Operation)> + '_ {
+        self.steps
+            .iter()
+            .filter_map(|step| step.operation.map(|operation| (step.id, operation)))
+    }
+}
+
+impl Display for Plan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for step in &self.steps {
+            writeln!(f, "{step}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, TimeDelta, Utc};
+
+    use super::{Operation, Plan};
+    use crate::config_file::{DesiredOffer, DesiredState};
+    use crate::offer::Offer;
+
+    const ID: u32 = 1234;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).expect("valid timestamp")
+    }
+
+    fn offer(active: bool, bumped_min_ago: Option<i64>) -> Offer {
+        Offer {
+            id: ID,
+            title: None,
+            active,
+            last_bumped: bumped_min_ago.map(|minutes| now() - TimeDelta::minutes(minutes)),
+        }
+    }
+
+    fn operation(state: DesiredState, current: Option<&Offer>) -> Option<Operation> {
+        let plan = Plan::new(
+            &[DesiredOffer { id: ID, state }],
+            &Vec::from_iter(current.cloned()),
+            now(),
+        );
+        let operations: Vec<_> = plan.operations().collect();
+        assert!(operations.len() <= 1);
+        operations.first().map(|&(id, operation)| {
+            assert_eq!(id, ID);
+            operation
+        })
+    }
+
+    #[test]
+    fn active() {
+        for (current, expected) in [
+            (Some(offer(true, None)), None),
+            (Some(offer(false, None)), Some(Operation::Activate)),
+            (None, None),
+        ] {
+            assert_eq!(
+                operation(DesiredState::Active, current.as_ref()),
+                expected,
+                "{current:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn inactive() {
+        for (current, expected) in [
+            (Some(offer(true, None)), Some(Operation::Deactivate)),
+            (Some(offer(false, None)), None),
+            (None, None),
+        ] {
+            assert_eq!(
+                operation(DesiredState::Inactive, current.as_ref()),
+                expected,
+                "{current:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn fresh() {
+        let fresh = DesiredState::Fresh { max_age_min: 60 };
+
+        for (current, expected) in [
+            (Some(offer(true, Some(30))), None),
+            (Some(offer(true, Some(60))), None),
+            (Some(offer(true, Some(61))), Some(Operation::Bump)),
+            (Some(offer(true, None)), None),
+            (Some(offer(false, Some(30))), Some(Operation::Activate)),
+            (Some(offer(false, None)), Some(Operation::Activate)),
+            (None, None),
+        ] {
+            assert_eq!(operation(fresh, current.as_ref()), expected, "{current:?}");
+        }
+    }
+
+    #[test]
+    fn only_desired_offers_are_planned() {
+        let current = [
+            offer(false, None),
+            Offer {
+                id: 42,
+                ..offer(false, None)
+            },
+        ];
+        let plan = Plan::new(
+            &[DesiredOffer {
+                id: ID,
+                state: DesiredState::Active,
+            }],
+            &current,
+            now(),
+        );
+        assert_eq!(
+            plan.operations().collect::<Vec<_>>(),
+            [(ID, Operation::Activate)]
+        );
+    }
+
+    #[test]
+    fn display() {
+        let fresh = DesiredState::Fresh { max_age_min: 60 };
+        let plan = Plan::new(
+            &[
+                DesiredOffer {
+                    id: ID,
+                    state: fresh,
+                },
+                DesiredOffer {
+                    id: 42,
+                    state: fresh,
+                },
+                DesiredOffer {
+                    id: 43,
+                    state: DesiredState::Active,
+                },
+            ],
+            &[
+                offer(true, None),
+                Offer {
+                    id: 43,
+                    ..offer(true, None)
+                },
+            ],
+            now(),
+        );
+        let lines: Vec<String> = plan
+            .to_string()
+            .lines()
+            .map(|line| line.rsplit(": ").next().unwrap_or_default().to_string())
+            .collect();
+        assert_eq!(
+            lines,
+            ["skipped, last bump unknown", "skipped", "up to date"]
+        );
+        assert_eq!(plan.operations().count(), 0);
+    }
+}
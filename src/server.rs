@@ -0,0 +1,355 @@
+use std::collections::BTreeSet;
+use std::future::pending;
+use std::net::SocketAddr;
+use std::path::Path as FilePath;
+use std::sync::Arc;
+
+use account::ManagedAccount;
+use anyhow::anyhow;
+use api_error::ApiError;
+use axum::extract::{Path, Request, State};
+use axum::http::StatusCode;
+use axum::http::header::AUTHORIZATION;
+use axum::middleware::{Next, from_fn_with_state};
+use axum::response::Response;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use log::{error, info};
+use report::Report;
+use serde::Serialize;
+use tokio::net::TcpListener;
+use tokio::signal;
+
+use crate::config_file::ConfigFile;
+use crate::metrics;
+use crate::notifier::Kind;
+use crate::offer::Offer;
+use crate::offer_ref::OfferRef;
+use crate::reconcile::Operation;
+use crate::settings::Settings;
+
+mod account;
+mod api_error;
+mod report;
+
+/// Shared state of the control API.
+#[derive(Debug)]
+struct Server {
+    token: String,
+    accounts: Vec<ManagedAccount>,
+}
+
+impl Server {
+    fn account(&self, user_name: &str) -> Result<&ManagedAccount, ApiError> {
+        self.accounts
+            .iter()
+            .find(|account| account.settings().user_name() == user_name)
+            .ok_or_else(|| ApiError::NotFound(format!("No such account: {user_name}")))
+    }
+}
+
+/// An account as listed by the control API.
+#[derive(Debug, Serialize)]
+struct AccountSummary {
+    user_name: String,
+    offers: BTreeSet<u32>,
+}
+
+/// Serve the local control API for the accounts of the given config file.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if the config file could not be read,
+/// no API token is configured or the server could not be started.
+pub async fn serve(config_file: &FilePath, listen: SocketAddr) -> anyhow::Result<()> {
+    let mut config_file = ConfigFile::load(config_file)?;
+    let token = config_file
+        .api_token
+        .take()
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| anyhow!("No API token configured"))?;
+    let server = Arc::new(Server {
+        token,
+        accounts: Vec::<Settings>::from(config_file)
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+    });
+
+    info!("Serving control API on {listen}");
+    axum::serve(TcpListener::bind(listen).await?, router(server.clone()))
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    for account in &server.accounts {
+        account.logout().await;
+    }
+
+    Ok(())
+}
+
+fn router(server: Arc<Server>) -> Router {
+    Router::new()
+        .route("/accounts", get(list_accounts))
+        .route("/accounts/{account}/offers", get(list_offers))
+        .route(
+            "/accounts/{account}/offers/{offer}/{operation}",
+            post(perform),
+        )
+        .route("/accounts/{account}/run", post(run))
+        .route("/accounts/{account}/report", get(report))
+        .route("/metrics", get(render_metrics))
+        .route_layer(from_fn_with_state(server.clone(), authorize))
+        .with_state(server)
+}
+
+/// Wait for Ctrl+C or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    let interrupt = async {
+        if let Err(error) = signal::ctrl_c().await {
+            error!("Could not listen for Ctrl+C: {error}");
+            pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match signal::unix::signal(signal::unix::SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(error) => {
+                error!("Could not listen for SIGTERM: {error}");
+                pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = pending::<()>();
+
+    tokio::select! {
+        () = interrupt => {},
+        () = terminate => {},
+    }
+
+    info!("Shutting down control API");
+}
+
+async fn authorize(
+    State(server): State<Arc<Server>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .is_some_and(|token| constant_time_eq(token.as_bytes(), server.token.as_bytes()))
+    {
+        Ok(next.run(request).await)
+    } else {
+        Err(ApiError::Unauthorized)
+    }
+}
+
+async fn list_accounts(State(server): State<Arc<Server>>) -> Json<Vec<AccountSummary>> {
+    Json(
+        server
+            .accounts
+            .iter()
+            .map(|account| AccountSummary {
+                user_name: account.settings().user_name().to_string(),
+                offers: account.settings().offers(),
+            })
+            .collect(),
+    )
+}
+
+async fn list_offers(
+    State(server): State<Arc<Server>>,
+    Path(account): Path<String>,
+) -> Result<Json<Vec<Offer>>, ApiError> {
+    Ok(Json(server.account(&account)?.offers().await?))
+}
+
+async fn perform(
+    State(server): State<Arc<Server>>,
+    Path((account, offer, operation)): Path<(String, String, Operation)>,
+) -> Result<(StatusCode, Json<Report>), ApiError> {
+    let account = server.account(&account)?;
+    let id = offer
+        .parse::<OfferRef>()
+        .and_then(|offer| account.resolve(&offer))
+        .map_err(|error| ApiError::NotFound(error.to_string()))?;
+
+    Ok(respond(account.perform(id, operation).await))
+}
+
+async fn run(
+    State(server): State<Arc<Server>>,
+    Path(account): Path<String>,
+) -> Result<(StatusCode, Json<Report>), ApiError> {
+    Ok(respond(server.account(&account)?.run().await))
+}
+
+async fn report(
+    State(server): State<Arc<Server>>,
+    Path(account): Path<String>,
+) -> Result<Json<Report>, ApiError> {
+    server
+        .account(&account)?
+        .report()
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("No report available for {account}")))
+}
+
+async fn render_metrics() -> String {
+    metrics::snapshot().to_string()
+}
+
+fn respond(report: Report) -> (StatusCode, Json<Report>) {
+    (
+        if report.event == Kind::Success {
+            StatusCode::OK
+        } else {
+            StatusCode::BAD_GATEWAY
+        },
+        Json(report),
+    )
+}
+
+/// Compare two byte slices in constant time to not leak the token via timing.
+fn constant_time_eq(lhs: &[u8], rhs: &[u8]) -> bool {
+    lhs.len() == rhs.len()
+        && lhs
+            .iter()
+            .zip(rhs)
+            .fold(0, |difference, (lhs, rhs)| difference | (lhs ^ rhs))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use reqwest::StatusCode;
+    use serde_json::json;
+    use tokio::net::TcpListener;
+
+    use super::{Server, router};
+    use crate::client::transport::stub::{self, Stub};
+    use crate::config_file::ConfigFile;
+    use crate::server::account::ManagedAccount;
+    use crate::settings::Settings;
+
+    const TOKEN: &str = "secret";
+    const OFFER_42: &str = "https://www.wg-gesucht.de/api/offers/42/users/1234567";
+    const OFFER_43: &str = "https://www.wg-gesucht.de/api/offers/43/users/1234567";
+
+    /// Serve the API for an account logged in via the stub and return its base URL.
+    async fn serve(stub: Arc<Stub>) -> String {
+        let config_file: ConfigFile = serde_json::from_value(json!({
+            "accounts": [{
+                "user_name": "user@example.com",
+                "password": "hunter22",
+                "offers": [
+                    {"id": 42, "name": "altona"},
+                    {"id": 43, "name": "eimsbuettel"},
+                ],
+                "groups": {"all": ["altona", "eimsbuettel"]},
+                "bump": [42],
+            }],
+        }))
+        .expect("valid config file");
+        let server = Arc::new(Server {
+            token: TOKEN.to_string(),
+            accounts: Vec::<Settings>::from(config_file)
+                .into_iter()
+                .map(|settings| ManagedAccount::logged_in(settings, stub::session(stub.clone())))
+                .collect(),
+        });
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bound listener");
+        let address = listener.local_addr().expect("local address");
+        tokio::spawn(async move { axum::serve(listener, router(server)).await });
+        format!("http://{address}/accounts/user@example.com")
+    }
+
+    async fn post(url: &str, token: Option<&str>) -> StatusCode {
+        let mut request = reqwest::Client::new().post(url);
+
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+
+        request.send().await.expect("response").status()
+    }
+
+    #[tokio::test]
+    async fn unauthorized() {
+        let stub = Arc::new(Stub::default());
+        let url = format!("{}/offers/42/bump", serve(stub.clone()).await);
+        assert_eq!(post(&url, None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(post(&url, Some("wrong")).await, StatusCode::UNAUTHORIZED);
+        assert!(stub.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn bump() {
+        let stub = Arc::new(Stub::new((0..4).map(|_| stub::status(200))));
+        let url = serve(stub.clone()).await;
+        assert_eq!(
+            post(&format!("{url}/offers/42/bump"), Some(TOKEN)).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            post(&format!("{url}/offers/eimsbuettel/bump"), Some(TOKEN)).await,
+            StatusCode::OK
+        );
+        let requests = stub.requests();
+        assert_eq!(
+            requests
+                .iter()
+                .map(|request| (request.method.as_str(), request.url.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                ("PATCH", OFFER_42),
+                ("PATCH", OFFER_42),
+                ("PATCH", OFFER_43),
+                ("PATCH", OFFER_43),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_offers() {
+        let stub = Arc::new(Stub::default());
+        let url = serve(stub.clone()).await;
+
+        for offer in ["7", "unknown", "@all", "@missing"] {
+            assert_eq!(
+                post(&format!("{url}/offers/{offer}/bump"), Some(TOKEN)).await,
+                StatusCode::NOT_FOUND,
+                "{offer}"
+            );
+        }
+
+        assert!(stub.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn failure_logs_out() {
+        let stub = Arc::new(Stub::new([stub::status(500), stub::status(200)]));
+        let url = serve(stub.clone()).await;
+        assert_eq!(
+            post(&format!("{url}/offers/altona/bump"), Some(TOKEN)).await,
+            StatusCode::BAD_GATEWAY
+        );
+        let requests = stub.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].method, "DELETE", "{}", requests[1].url);
+    }
+}
@@ -0,0 +1,166 @@
+use std::sync::{Mutex, PoisonError};
+
+use anyhow::anyhow;
+use tokio::sync::Mutex as AsyncMutex;
+
+use super::Report;
+use crate::client::Session;
+use crate::error::{Error, FailedUpdates};
+use crate::offer::Offer;
+use crate::offer_ref::OfferRef;
+use crate::reconcile::Operation;
+use crate::settings::Settings;
+
+/// An account managed by the server.
+///
+/// The session is kept across requests, so that the account does not log in on every request.
+#[derive(Debug)]
+pub struct ManagedAccount {
+    settings: Settings,
+    session: AsyncMutex<Option<Session>>,
+    report: Mutex<Option<Report>>,
+}
+
+impl ManagedAccount {
+    /// Return the account's settings.
+    #[must_use]
+    pub const fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Return the ID of the referenced offer.
+    ///
+    /// IDs must be configured in the account, other references are resolved via its catalog.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] if the reference cannot be resolved
+    /// or does not refer to exactly one offer.
+    pub fn resolve(&self, offer: &OfferRef) -> anyhow::Result<u32> {
+        let catalog = self.settings.catalog();
+
+        if let OfferRef::Id(id) = offer {
+            return if self.settings.offers().contains(id) || catalog.name(*id).is_some() {
+                Ok(*id)
+            } else {
+                Err(anyhow!("Offer not configured: {id}"))
+            };
+        }
+
+        match catalog.resolve(offer)?.as_slice() {
+            [id] => Ok(*id),
+            ids => Err(anyhow!(
+                "{offer} refers to {} offers instead of one",
+                ids.len()
+            )),
+        }
+    }
+
+    /// List the offers of the account.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`Error`] if the login failed or the offers could not be listed.
+    pub async fn offers(&self) -> Result<Vec<Offer>, Error> {
+        self.with_session(async |session| session.offers().await.map_err(Error::Listing))
+            .await
+    }
+
+    /// Perform an operation on an offer and return the respective report.
+    pub async fn perform(&self, id: u32, operation: Operation) -> Report {
+        let result = self
+            .with_session(async |session| {
+                let mut failed_updates = FailedUpdates::default();
+                self.settings
+                    .perform(session, id, operation, &mut failed_updates)
+                    .await;
+
+                if failed_updates.is_empty() {
+                    Ok(())
+                } else {
+                    Err(failed_updates.into())
+                }
+            })
+            .await;
+        self.record(&result)
+    }
+
+    /// Apply the account's settings and return the respective report.
+    pub async fn run(&self) -> Report {
+        let result = self
+            .with_session(async |session| self.settings.update(session).await)
+            .await;
+        self.record(&result)
+    }
+
+    /// Run a function with the account's session, logging in if there is no active session.
+    ///
+    /// If the function fails, the session is logged out and discarded,
+    /// so that the next call logs in again.
+    async fn with_session<T>(
+        &self,
+        function: impl AsyncFnOnce(&Session) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let mut guard = self.session.lock().await;
+        let session = match guard.take() {
+            Some(session) => session,
+            None => self.settings.login().await?,
+        };
+        let result = function(&session).await;
+
+        if result.is_ok() {
+            *guard = Some(session);
+        } else {
+            drop(guard);
+            self.settings.logout(session).await;
+        }
+
+        result
+    }
+
+    /// Log out of the cached session, if any.
+    pub async fn logout(&self) {
+        let session = self.session.lock().await.take();
+
+        if let Some(session) = session {
+            self.settings.logout(session).await;
+        }
+    }
+
+    fn record(&self, result: &Result<(), Error>) -> Report {
+        let report = Report::from(&self.settings.notification(result));
+        *self.report.lock().unwrap_or_else(PoisonError::into_inner) = Some(report.clone());
+        report
+    }
+
+    /// Return the report of the last run.
+    #[must_use]
+    pub fn report(&self) -> Option<Report> {
+        self.report
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+#[cfg(test)]
+impl ManagedAccount {
+    /// Create an account that is already logged in with the given session.
+    pub fn logged_in(settings: Settings, session: Session) -> Self {
+        Self {
+            settings,
+            session: AsyncMutex::new(Some(session)),
+            report: Mutex::new(None),
+        }
+    }
+}
+
+impl From<Settings> for ManagedAccount {
+    fn from(settings: Settings) -> Self {
+        Self {
+            settings,
+            session: AsyncMutex::new(None),
+            report: Mutex::new(None),
+        }
+    }
+}
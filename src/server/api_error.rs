@@ -0,0 +1,33 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+
+use crate::error::Error;
+
+/// Errors returned by the control API.
+#[derive(Debug)]
+pub enum ApiError {
+    /// The bearer token is missing or invalid.
+    Unauthorized,
+    /// The requested account or offer is not configured.
+    NotFound(String),
+    /// Communicating with wg-gesucht failed.
+    Upstream(Error),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Unauthorized => {
+                (StatusCode::UNAUTHORIZED, "Invalid bearer token").into_response()
+            }
+            Self::NotFound(message) => (StatusCode::NOT_FOUND, message).into_response(),
+            Self::Upstream(error) => (StatusCode::BAD_GATEWAY, error.to_string()).into_response(),
+        }
+    }
+}
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        Self::Upstream(error)
+    }
+}
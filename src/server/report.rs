@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::notifier::{Kind, Notification};
+
+/// Report of the last run on an account.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Report {
+    timestamp: DateTime<Utc>,
+    pub(crate) event: Kind,
+    message: String,
+    offers: Vec<u32>,
+}
+
+impl From<&Notification<'_>> for Report {
+    fn from(notification: &Notification<'_>) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            event: notification.kind,
+            message: notification.message.clone(),
+            offers: notification.offers.clone(),
+        }
+    }
+}
@@ -1,14 +1,23 @@
 use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use anyhow::anyhow;
-use log::{error, info};
-use serde_rw::FromFile;
+use chrono::Utc;
+use log::{error, info, warn};
 
-use crate::args::{Action, Args, Mode, Parameters};
-use crate::client::{Client, TIMEOUT, USER_AGENT};
-use crate::config_file::{Account, ConfigFile};
+use crate::args::{Action, Parameters};
+use crate::catalog::Catalog;
+use crate::client::{Client, Session, TIMEOUT, USER_AGENT};
+use crate::config_file::{Account, ConfigFile, DesiredOffer, DesiredState, Scraping};
+use crate::credentials::{Credentials, Verification};
 use crate::error::{Error, FailedUpdates};
+use crate::metrics;
+use crate::notifier::{Kind, Notification, Notifier};
+use crate::offer_ref::OfferRef;
+use crate::reconcile::{Operation, Plan};
+use crate::state::state_dir;
 
 /// Source-agnostic settings
 ///
@@ -20,122 +29,538 @@ pub struct Settings {
     password: String,
     user_agent: String,
     timeout: Duration,
+    autologin: bool,
+    verification: Verification,
+    catalog: Catalog,
     activate: Vec<u32>,
     bump: Vec<u32>,
     deactivate: Vec<u32>,
+    desired: Vec<DesiredOffer>,
+    notifier: Notifier,
+    alert_recipients: Vec<String>,
+    metrics_file: Option<PathBuf>,
+    scraping: Scraping,
 }
 
 impl Settings {
+    /// Load the settings of all accounts from a config file.
+    ///
+    /// # Errors
+    /// Return an [`anyhow::Error`] if the config file could not be read.
+    pub fn load(config_file: impl AsRef<Path>) -> anyhow::Result<Vec<Self>> {
+        ConfigFile::load(config_file).map(Into::into)
+    }
+
+    /// Create the settings of an account including the global settings of its config file.
+    #[must_use]
+    pub fn from_config(config_file: &ConfigFile, account: &Account) -> Self {
+        let settings = Self::from(account.clone());
+        Self {
+            notifier: Notifier::new(
+                config_file.webhooks.clone(),
+                config_file.smtp.clone(),
+                state_dir(config_file.state_dir.clone()),
+                settings.user_agent.clone(),
+                settings.timeout,
+            ),
+            metrics_file: config_file.metrics_file.clone(),
+            scraping: config_file.scraping.clone(),
+            ..settings
+        }
+    }
+
+    /// Return the IDs of all offers configured for the account.
+    #[must_use]
+    pub fn offers(&self) -> BTreeSet<u32> {
+        self.activate
+            .iter()
+            .chain(&self.bump)
+            .chain(&self.deactivate)
+            .copied()
+            .chain(self.desired.iter().map(|desired| desired.id))
+            .collect()
+    }
+
+    /// Return the catalog of the account's named offers.
+    #[must_use]
+    pub const fn catalog(&self) -> &Catalog {
+        &self.catalog
+    }
+
+    /// Return the user name of the account.
+    #[must_use]
+    pub fn user_name(&self) -> &str {
+        &self.user_name
+    }
+
     /// Apply the settings.
     ///
     /// # Errors
     /// Return an [`Vec<anyhow::Error>`] containing any errors that occurred.
     pub async fn apply(self) -> Result<(), Error> {
-        let session = match Client::new(self.timeout, Cow::Owned(self.user_agent))
-            .login(&self.user_name, &self.password)
-            .await
+        let result = self.run().await;
+        let outcome = self.notification(&result);
+        self.notifier.notify(&outcome).await;
+        self.notifier.alert(&outcome, &self.alert_recipients).await;
+
+        if let Some(metrics_file) = &self.metrics_file
+            && let Err(error) = metrics::write_textfile(metrics_file, self.notifier.state_dir())
         {
-            Ok(session) => session,
-            Err(error) => {
-                error!("Login failed: {error}");
-                return Err(Error::Login(error));
-            }
-        };
+            error!(
+                "Could not write metrics to {}: {error}",
+                metrics_file.display()
+            );
+        }
+
+        result
+    }
+
+    async fn run(&self) -> Result<(), Error> {
+        self.with_session(async |session| self.update(session).await)
+            .await
+    }
 
+    /// Update the offers using an existing session.
+    ///
+    /// If the offers could not be listed for reconciliation, the remaining updates are still
+    /// performed and the listing error is reported along with the failed updates.
+    ///
+    /// # Errors
+    /// Return an [`Error`] if the offers could not be listed or some updates failed.
+    pub async fn update(&self, session: &Session) -> Result<(), Error> {
         let mut failed_updates = FailedUpdates::default();
 
         for &id in &self.deactivate {
-            info!("Deactivating offer: {id}");
-
-            if let Err(error) = session.deactivate(id).await {
-                error!("Could not deactivate offer {id}: {error}");
-                failed_updates.deactivate.insert(id, error);
-            }
+            self.perform(session, id, Operation::Deactivate, &mut failed_updates)
+                .await;
         }
 
         for &id in &self.activate {
-            info!("Activating offer: {id}");
-
-            if let Err(error) = session.activate(id).await {
-                error!("Could not activate offer {id}: {error}");
-                failed_updates.activate.insert(id, error);
-            }
+            self.perform(session, id, Operation::Activate, &mut failed_updates)
+                .await;
         }
 
         for &id in &self.bump {
-            info!("Bumping offer: {id}");
+            self.perform(session, id, Operation::Bump, &mut failed_updates)
+                .await;
+        }
 
-            if let Err(error) = session.bump(id).await {
-                error!("Could not bump offer {id}: {error}");
-                failed_updates.bump.insert(id, error);
+        if !self.desired.is_empty() {
+            match self.reconcile(session).await {
+                Ok(plan) => {
+                    for (id, operation) in plan.operations() {
+                        self.perform(session, id, operation, &mut failed_updates)
+                            .await;
+                    }
+                }
+                Err(error) => failed_updates.listing = Some(error),
             }
         }
 
+        if !self.notifier.is_empty() {
+            self.notify_deactivated(session).await;
+        }
+
         if failed_updates.is_empty() {
             Ok(())
         } else {
             Err(failed_updates.into())
         }
     }
+
+    /// Plan the operations required to reach the desired state of the offers.
+    ///
+    /// # Errors
+    /// Return an [`Error`] if the login failed or the offers could not be listed.
+    pub async fn plan(&self) -> Result<Plan, Error> {
+        self.with_session(async |session| self.reconcile(session).await.map_err(Error::Listing))
+            .await
+    }
+
+    async fn notify_deactivated(&self, session: &Session) {
+        let offers = match session.offers().await {
+            Ok(offers) => offers,
+            Err(error) => {
+                warn!("Could not check for deactivated offers: {error}");
+                return;
+            }
+        };
+        let deactivated: Vec<u32> = offers
+            .iter()
+            .filter(|offer| !offer.active && self.should_be_active(offer.id))
+            .map(|offer| offer.id)
+            .collect();
+
+        if !deactivated.is_empty() {
+            self.notifier
+                .notify(&Notification::new(
+                    Kind::OffersDeactivated,
+                    &self.user_name,
+                    format!(
+                        "{} offer(s) are deactivated: {deactivated:?}",
+                        deactivated.len()
+                    ),
+                    deactivated,
+                ))
+                .await;
+        }
+    }
+
+    fn should_be_active(&self, id: u32) -> bool {
+        !self.deactivate.contains(&id)
+            && (self.activate.contains(&id)
+                || self.bump.contains(&id)
+                || self
+                    .desired
+                    .iter()
+                    .any(|desired| desired.id == id && desired.state != DesiredState::Inactive))
+    }
+
+    /// Return the notification describing the outcome of a run.
+    #[must_use]
+    pub fn notification(&self, result: &Result<(), Error>) -> Notification<'_> {
+        match result {
+            Ok(()) => Notification::new(
+                Kind::Success,
+                &self.user_name,
+                "All offers have been updated.".to_string(),
+                Vec::new(),
+            ),
+            Err(Error::Login(error)) => Notification::new(
+                Kind::LoginFailed,
+                &self.user_name,
+                error.to_string(),
+                Vec::new(),
+            ),
+            Err(Error::Listing(error)) => Notification::new(
+                Kind::UpdatesFailed,
+                &self.user_name,
+                format!("Could not list offers: {error}"),
+                Vec::new(),
+            ),
+            Err(Error::Updates(failed_updates)) => Notification::new(
+                Kind::UpdatesFailed,
+                &self.user_name,
+                failed_updates.labeled(&self.catalog).to_string(),
+                failed_updates.offers().into_iter().collect(),
+            ),
+        }
+    }
+
+    /// Return a client with the account's user agent and timeout.
+    #[must_use]
+    pub fn client(&self) -> Client {
+        Client::new(
+            self.timeout,
+            Cow::Owned(self.user_agent.clone()),
+            self.scraping.clone(),
+        )
+    }
+
+    /// Log into the account.
+    ///
+    /// # Errors
+    /// Return an [`Error::Login`] if the login failed.
+    pub async fn login(&self) -> Result<Session, Error> {
+        let result = self
+            .client()
+            .login(Credentials::new(
+                self.user_name.clone(),
+                self.password.clone(),
+                self.autologin,
+                self.verification.clone(),
+            ))
+            .await;
+        metrics::record_login(&self.user_name, result.is_ok());
+        result.map_err(|error| {
+            error!("Login failed: {error}");
+            Error::Login(error)
+        })
+    }
+
+    /// Log out of the account, so that the session does not linger on the server.
+    ///
+    /// Failures are only logged, since the run itself is not affected by them.
+    pub async fn logout(&self, session: Session) {
+        match session.logout().await {
+            Ok(_) => info!("Logged out of account: {}", self.user_name),
+            Err(error) => warn!("Could not log out of account {}: {error}", self.user_name),
+        }
+    }
+
+    /// Log into the account, pass the session to `f` and log out again.
+    ///
+    /// The session is logged out even if `f` fails.
+    ///
+    /// # Errors
+    /// Return an [`Error::Login`] if the login failed or the error returned by `f`.
+    pub async fn with_session<T, E>(
+        &self,
+        f: impl AsyncFnOnce(&Session) -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        E: From<Error>,
+    {
+        let session = self.login().await?;
+        let result = f(&session).await;
+        self.logout(session).await;
+        result
+    }
+
+    async fn reconcile(&self, session: &Session) -> anyhow::Result<Plan> {
+        let offers = session.offers().await.inspect_err(|error| {
+            error!("Could not list offers: {error}");
+        })?;
+
+        for offer in &offers {
+            metrics::record_state(&self.user_name, offer.id, offer.active);
+        }
+
+        Ok(Plan::new(&self.desired, &offers, Utc::now()))
+    }
+
+    /// Perform an operation on an offer and record failures.
+    pub async fn perform(
+        &self,
+        session: &Session,
+        id: u32,
+        operation: Operation,
+        failed_updates: &mut FailedUpdates,
+    ) {
+        let (result, failures) = match operation {
+            Operation::Activate => {
+                info!("Activating offer: {}", self.catalog.label(id));
+                (session.activate(id).await, &mut failed_updates.activate)
+            }
+            Operation::Deactivate => {
+                info!("Deactivating offer: {}", self.catalog.label(id));
+                (session.deactivate(id).await, &mut failed_updates.deactivate)
+            }
+            Operation::Bump => {
+                info!("Bumping offer: {}", self.catalog.label(id));
+                (session.bump(id).await, &mut failed_updates.bump)
+            }
+        };
+
+        match result {
+            Ok(_) => {
+                metrics::record_state(&self.user_name, id, operation != Operation::Deactivate);
+
+                if operation == Operation::Bump {
+                    metrics::record_bump(&self.user_name, id);
+                }
+            }
+            Err(error) => {
+                error!(
+                    "Could not {operation} offer {}: {error}",
+                    self.catalog.label(id)
+                );
+                failures.insert(id, error);
+            }
+        }
+    }
 }
 
 impl From<Account> for Settings {
     fn from(account: Account) -> Self {
+        let [activate, bump, deactivate] = [&account.activate, &account.bump, &account.deactivate]
+            .map(|offers| account.ids(offers));
+        let catalog = account.catalog();
+        let desired = account.desired();
         Self {
             user_name: account.user_name,
             password: account.password,
             user_agent: account.user_agent.unwrap_or_else(|| USER_AGENT.to_string()),
             timeout: account.timeout_sec.map_or(TIMEOUT, Duration::from_secs),
-            activate: account.activate,
-            bump: account.bump,
-            deactivate: account.deactivate,
+            autologin: account.autologin.unwrap_or(true),
+            verification: account
+                .totp_secret
+                .map_or(Verification::Prompt, Verification::TotpSecret),
+            catalog,
+            activate,
+            bump,
+            deactivate,
+            desired,
+            notifier: Notifier::default(),
+            alert_recipients: account.alert_recipients,
+            metrics_file: None,
+            scraping: Scraping::default(),
         }
     }
 }
 
-impl From<Parameters> for Settings {
-    fn from(settings: Parameters) -> Self {
-        match settings.action {
-            Action::Activate { offers } => Self {
-                user_name: settings.user_name,
-                password: settings.password,
-                user_agent: settings.user_agent,
-                timeout: Duration::from_secs(settings.timeout),
-                activate: offers,
-                bump: Vec::with_capacity(0),
-                deactivate: Vec::with_capacity(0),
-            },
-            Action::Bump { offers } => Self {
-                user_name: settings.user_name,
-                password: settings.password,
-                user_agent: settings.user_agent,
-                timeout: Duration::from_secs(settings.timeout),
-                activate: Vec::with_capacity(0),
-                bump: offers,
-                deactivate: Vec::with_capacity(0),
-            },
-            Action::Deactivate { offers } => Self {
-                user_name: settings.user_name,
-                password: settings.password,
-                user_agent: settings.user_agent,
-                timeout: Duration::from_secs(settings.timeout),
-                activate: Vec::with_capacity(0),
-                bump: Vec::with_capacity(0),
-                deactivate: offers,
-            },
-        }
+impl TryFrom<Parameters> for Settings {
+    type Error = anyhow::Error;
+
+    fn try_from(parameters: Parameters) -> anyhow::Result<Self> {
+        let catalog = match &parameters.config_file {
+            Some(config_file) => ConfigFile::load(config_file)?
+                .accounts
+                .iter()
+                .find(|account| account.user_name == parameters.user_name)
+                .map(Account::catalog)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "No such account in {}: {}",
+                        config_file.display(),
+                        parameters.user_name
+                    )
+                })?,
+            None => Catalog::default(),
+        };
+        let (operation, mut offers, tags, groups) = match parameters.action {
+            Action::Activate { offers, tag, group } => (Operation::Activate, offers, tag, group),
+            Action::Bump { offers, tag, group } => (Operation::Bump, offers, tag, group),
+            Action::Deactivate { offers, tag, group } => {
+                (Operation::Deactivate, offers, tag, group)
+            }
+        };
+        offers.extend(tags.into_iter().map(OfferRef::Tags));
+        offers.extend(groups.into_iter().map(OfferRef::Group));
+        let offers = catalog.select(&offers)?;
+        let selected = |candidate| {
+            if candidate == operation {
+                offers.clone()
+            } else {
+                Vec::with_capacity(0)
+            }
+        };
+
+        Ok(Self {
+            user_name: parameters.user_name,
+            password: parameters.password,
+            user_agent: parameters.user_agent,
+            timeout: Duration::from_secs(parameters.timeout),
+            autologin: !parameters.no_autologin,
+            verification: parameters
+                .otp
+                .map_or(Verification::Prompt, Verification::Code),
+            activate: selected(Operation::Activate),
+            bump: selected(Operation::Bump),
+            deactivate: selected(Operation::Deactivate),
+            catalog,
+            desired: Vec::with_capacity(0),
+            notifier: Notifier::default(),
+            alert_recipients: Vec::with_capacity(0),
+            metrics_file: None,
+            scraping: Scraping::default(),
+        })
     }
 }
 
-impl TryFrom<Args> for Vec<Settings> {
-    type Error = anyhow::Error;
+impl From<ConfigFile> for Vec<Settings> {
+    fn from(config_file: ConfigFile) -> Self {
+        config_file
+            .accounts
+            .iter()
+            .map(|account| Settings::from_config(&config_file, account))
+            .collect()
+    }
+}
 
-    fn try_from(args: Args) -> Result<Self, Self::Error> {
-        match args.mode {
-            Mode::Cli(settings) => Ok(vec![settings.into()]),
-            Mode::ConfigFile { config_file } => ConfigFile::from_file(config_file)
-                .map_err(|error| anyhow!("{error}"))
-                .map(|config_file| config_file.accounts.into_iter().map(Into::into).collect()),
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use super::Settings;
+    use crate::catalog::Catalog;
+    use crate::client::transport::stub::{self, Stub};
+    use crate::client::{TIMEOUT, USER_AGENT};
+    use crate::config_file::Scraping;
+    use crate::credentials::Verification;
+    use crate::error::{Error, FailedUpdates};
+    use crate::notifier::Notifier;
+
+    fn settings(activate: Vec<u32>, bump: Vec<u32>, deactivate: Vec<u32>) -> Settings {
+        Settings {
+            user_name: "user@example.com".to_string(),
+            password: "hunter22".to_string(),
+            user_agent: USER_AGENT.to_string(),
+            timeout: TIMEOUT,
+            autologin: true,
+            verification: Verification::Prompt,
+            catalog: Catalog::default(),
+            activate,
+            bump,
+            deactivate,
+            desired: Vec::new(),
+            notifier: Notifier::default(),
+            alert_recipients: Vec::new(),
+            metrics_file: None,
+            scraping: Scraping::default(),
         }
     }
+
+    async fn failed_updates(settings: &Settings, stub: Arc<Stub>) -> FailedUpdates {
+        match settings.update(&stub::session(stub)).await {
+            Err(Error::Updates(failed_updates)) => *failed_updates,
+            other => panic!("Expected failed updates, got {other:?}"),
+        }
+    }
+
+    fn errors(failures: &BTreeMap<u32, anyhow::Error>) -> Vec<(u32, String)> {
+        failures
+            .iter()
+            .map(|(id, error)| (*id, error.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn server_error() {
+        let failed_updates = failed_updates(
+            &settings(vec![1], vec![2], Vec::new()),
+            Arc::new(Stub::new([stub::status(200), stub::status(500)])),
+        )
+        .await;
+        assert!(failed_updates.activate.is_empty());
+        assert!(failed_updates.deactivate.is_empty());
+        assert_eq!(failed_updates.bump.keys().copied().collect::<Vec<_>>(), [2]);
+        assert!(failed_updates.bump[&2].to_string().contains("500"));
+        assert!(failed_updates.listing.is_none());
+    }
+
+    #[tokio::test]
+    async fn timeout() {
+        let failed_updates = failed_updates(
+            &settings(Vec::new(), Vec::new(), vec![3]),
+            Arc::new(Stub::new([stub::timeout()])),
+        )
+        .await;
+        assert_eq!(
+            errors(&failed_updates.deactivate),
+            [(3, "operation timed out".to_string())]
+        );
+        assert!(failed_updates.bump.is_empty());
+    }
+
+    #[tokio::test]
+    async fn missing_cookie() {
+        let stub = Arc::new(Stub::new([
+            stub::status(401),
+            stub::login(&["X-Dev-Ref-No=devref456"]),
+        ]));
+        let failed_updates =
+            failed_updates(&settings(Vec::new(), vec![4], Vec::new()), stub.clone()).await;
+        assert_eq!(
+            stub.requests()
+                .iter()
+                .map(|request| (request.method.as_str(), request.url.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    "PATCH",
+                    "https://www.wg-gesucht.de/api/offers/4/users/1234567"
+                ),
+                (
+                    "POST",
+                    "https://www.wg-gesucht.de/ajax/sessions.php?action=login"
+                ),
+            ]
+        );
+        assert_eq!(
+            errors(&failed_updates.bump),
+            [(4, "X-Access-Token not found in cookies".to_string())]
+        );
+    }
 }
@@ -0,0 +1,53 @@
+use std::env::var_os;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use log::warn;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_rw::{Error, FromFile, ToFile};
+
+/// Environment variable set by systemd if `StateDirectory=` is configured.
+const STATE_DIRECTORY: &str = "STATE_DIRECTORY";
+
+/// State that is persisted across runs as a file in the state directory.
+pub trait PersistentState: Default + DeserializeOwned + Serialize {
+    /// File name of the state within the state directory.
+    const FILE_NAME: &'static str;
+
+    /// Load the state from the state directory.
+    ///
+    /// If the state cannot be read, a warning is logged and the default state is returned.
+    fn load(state_dir: &Path) -> Self {
+        match Self::from_file(state_dir.join(Self::FILE_NAME)) {
+            Ok(state) => state,
+            Err(Error::Io(error)) if error.kind() == ErrorKind::NotFound => Self::default(),
+            Err(error) => {
+                warn!("Could not load state from {}: {error}", Self::FILE_NAME);
+                Self::default()
+            }
+        }
+    }
+
+    /// Store the state in the state directory.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] if the state could not be written.
+    fn save(&self, state_dir: &Path) -> anyhow::Result<()> {
+        self.write_to_file_pretty(state_dir.join(Self::FILE_NAME))
+            .map_err(|error| anyhow!("{error}"))
+    }
+}
+
+/// Return the state directory.
+///
+/// If no state directory is configured, the one provided by systemd is used,
+/// falling back to the current working directory.
+#[must_use]
+pub fn state_dir(configured: Option<PathBuf>) -> PathBuf {
+    configured
+        .or_else(|| var_os(STATE_DIRECTORY).map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
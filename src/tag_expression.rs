@@ -0,0 +1,272 @@
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+
+use anyhow::anyhow;
+
+/// Maximum nesting of negations and parentheses, so that parsing cannot overflow the stack.
+const MAX_DEPTH: usize = 32;
+
+/// Boolean expression over the tags of an offer, e.g. `haus-a & !winter | haus-b`.
+///
+/// `!` binds stronger than `&`, which binds stronger than `|`. Parentheses group sub-expressions.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum TagExpression {
+    /// The offer has the tag.
+    Tag(String),
+    /// The offer does not match the expression.
+    Not(Box<Self>),
+    /// The offer matches both expressions.
+    And(Box<Self>, Box<Self>),
+    /// The offer matches either expression.
+    Or(Box<Self>, Box<Self>),
+}
+
+impl TagExpression {
+    /// Return `true` iff an offer with the given tags matches the expression.
+    #[must_use]
+    pub fn matches(&self, tags: &[String]) -> bool {
+        match self {
+            Self::Tag(tag) => tags.contains(tag),
+            Self::Not(expression) => !expression.matches(tags),
+            Self::And(lhs, rhs) => lhs.matches(tags) && rhs.matches(tags),
+            Self::Or(lhs, rhs) => lhs.matches(tags) || rhs.matches(tags),
+        }
+    }
+}
+
+impl FromStr for TagExpression {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            source: s,
+            chars: s.chars().peekable(),
+            depth: 0,
+        };
+        let expression = parser.or()?;
+
+        parser.peek().map_or(Ok(expression), |char| {
+            Err(anyhow!("Unexpected {char:?} in tag expression {s:?}"))
+        })
+    }
+}
+
+impl Display for TagExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tag(tag) => write!(f, "{tag}"),
+            Self::Not(expression) => write!(f, "!{expression}"),
+            Self::And(lhs, rhs) => write!(f, "({lhs} & {rhs})"),
+            Self::Or(lhs, rhs) => write!(f, "({lhs} | {rhs})"),
+        }
+    }
+}
+
+struct Parser<'source> {
+    source: &'source str,
+    chars: Peekable<Chars<'source>>,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn or(&mut self) -> anyhow::Result<TagExpression> {
+        let mut expression = self.and()?;
+
+        while self.accept('|') {
+            expression = TagExpression::Or(Box::new(expression), Box::new(self.and()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn and(&mut self) -> anyhow::Result<TagExpression> {
+        let mut expression = self.not()?;
+
+        while self.accept('&') {
+            expression = TagExpression::And(Box::new(expression), Box::new(self.not()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn not(&mut self) -> anyhow::Result<TagExpression> {
+        if self.depth >= MAX_DEPTH {
+            return Err(anyhow!(
+                "Tag expression {:?} is nested too deeply",
+                self.source
+            ));
+        }
+
+        self.depth += 1;
+        let expression = self.operand();
+        self.depth -= 1;
+        expression
+    }
+
+    fn operand(&mut self) -> anyhow::Result<TagExpression> {
+        if self.accept('!') {
+            return Ok(TagExpression::Not(Box::new(self.not()?)));
+        }
+
+        if self.accept('(') {
+            let expression = self.or()?;
+
+            return if self.accept(')') {
+                Ok(expression)
+            } else {
+                Err(anyhow!("Missing ')' in tag expression {:?}", self.source))
+            };
+        }
+
+        self.tag()
+    }
+
+    fn tag(&mut self) -> anyhow::Result<TagExpression> {
+        self.peek();
+        let mut tag = String::new();
+
+        while let Some(char) = self.chars.next_if(|&char| is_tag_char(char)) {
+            tag.push(char);
+        }
+
+        if tag.is_empty() {
+            Err(anyhow!("Missing tag in tag expression {:?}", self.source))
+        } else {
+            Ok(TagExpression::Tag(tag))
+        }
+    }
+
+    /// Skip whitespace and return the next character without consuming it.
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.next_if(|char| char.is_whitespace()).is_some() {}
+
+        self.chars.peek().copied()
+    }
+
+    /// Consume the next character if it is the expected one.
+    fn accept(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.chars.next();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+fn is_tag_char(char: char) -> bool {
+    char.is_alphanumeric() || matches!(char, '-' | '_' | '.' | ':' | '/')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_DEPTH, TagExpression};
+
+    fn tag(tag: &str) -> TagExpression {
+        TagExpression::Tag(tag.to_string())
+    }
+
+    fn parse(s: &str) -> TagExpression {
+        s.parse().expect("Expression should be valid.")
+    }
+
+    fn error(s: &str) -> String {
+        s.parse::<TagExpression>()
+            .expect_err("Expression should be invalid.")
+            .to_string()
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            parse("a | b & !c"),
+            TagExpression::Or(
+                Box::new(tag("a")),
+                Box::new(TagExpression::And(
+                    Box::new(tag("b")),
+                    Box::new(TagExpression::Not(Box::new(tag("c"))))
+                ))
+            )
+        );
+        assert_eq!(
+            parse("a & b | c & d"),
+            TagExpression::Or(
+                Box::new(TagExpression::And(Box::new(tag("a")), Box::new(tag("b")))),
+                Box::new(TagExpression::And(Box::new(tag("c")), Box::new(tag("d"))))
+            )
+        );
+        assert_eq!(
+            parse("a | b | c"),
+            TagExpression::Or(
+                Box::new(TagExpression::Or(Box::new(tag("a")), Box::new(tag("b")))),
+                Box::new(tag("c"))
+            )
+        );
+    }
+
+    #[test]
+    fn parentheses() {
+        assert_eq!(
+            parse("(a | b) & !(c)"),
+            TagExpression::And(
+                Box::new(TagExpression::Or(Box::new(tag("a")), Box::new(tag("b")))),
+                Box::new(TagExpression::Not(Box::new(tag("c"))))
+            )
+        );
+        assert_eq!(parse(" ( ( haus-a ) ) "), tag("haus-a"));
+        assert_eq!(error("(a | b"), "Missing ')' in tag expression \"(a | b\"");
+    }
+
+    #[test]
+    fn trailing_input() {
+        assert_eq!(error("a b"), "Unexpected 'b' in tag expression \"a b\"");
+        assert_eq!(error("a)"), "Unexpected ')' in tag expression \"a)\"");
+    }
+
+    #[test]
+    fn empty_tags() {
+        for expression in ["", " ", "a &", "| a", "!", "()"] {
+            assert_eq!(
+                error(expression),
+                format!("Missing tag in tag expression {expression:?}")
+            );
+        }
+    }
+
+    #[test]
+    fn depth() {
+        let nested = |depth| format!("{}a", "!".repeat(depth));
+        assert!(nested(MAX_DEPTH - 1).parse::<TagExpression>().is_ok());
+        assert_eq!(
+            error(&nested(MAX_DEPTH)),
+            format!(
+                "Tag expression {:?} is nested too deeply",
+                nested(MAX_DEPTH)
+            )
+        );
+
+        let parenthesized = format!("{}a{}", "(".repeat(10_000), ")".repeat(10_000));
+        assert_eq!(
+            error(&parenthesized),
+            format!("Tag expression {parenthesized:?} is nested too deeply")
+        );
+    }
+
+    #[test]
+    fn matches() {
+        let expression = parse("(haus-a | haus-b) & !winter");
+        let tags = |tags: &[&str]| tags.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert!(expression.matches(&tags(&["haus-a"])));
+        assert!(expression.matches(&tags(&["haus-b", "sommer"])));
+        assert!(!expression.matches(&tags(&["haus-a", "winter"])));
+        assert!(!expression.matches(&tags(&[])));
+    }
+
+    #[test]
+    fn display() {
+        let expression = parse("a | b & !(c | d)");
+        assert_eq!(expression.to_string(), "(a | (b & !(c | d)))");
+        assert_eq!(parse(&expression.to_string()), expression);
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+
+const OPEN: &str = "{{";
+const CLOSE: &str = "}}";
+
+/// Render a template by replacing `{{name}}` placeholders with the respective variables.
+///
+/// # Errors
+///
+/// Return an [`anyhow::Error`] if a placeholder is not closed or refers to an unknown variable.
+pub fn render(template: &str, variables: &BTreeMap<String, String>) -> anyhow::Result<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some((before, after)) = rest.split_once(OPEN) {
+        let (name, after) = after
+            .split_once(CLOSE)
+            .ok_or_else(|| anyhow!("Unclosed placeholder in template"))?;
+        rendered.push_str(before);
+        rendered.push_str(
+            variables
+                .get(name.trim())
+                .ok_or_else(|| anyhow!("Unknown template variable: {}", name.trim()))?,
+        );
+        rest = after;
+    }
+
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::render;
+
+    fn variables() -> BTreeMap<String, String> {
+        [
+            ("rent".to_string(), "450".to_string()),
+            ("room".to_string(), "{{rent}} & <b>".to_string()),
+        ]
+        .into()
+    }
+
+    #[test]
+    fn replaces_placeholders() {
+        assert_eq!(
+            render("Rent: {{rent}} €, {{ rent }}€", &variables()).expect("rendered"),
+            "Rent: 450 €, 450€"
+        );
+    }
+
+    #[test]
+    fn keeps_text_without_placeholders() {
+        assert_eq!(
+            render("No {placeholders} here }}", &variables()).expect("rendered"),
+            "No {placeholders} here }}"
+        );
+    }
+
+    #[test]
+    fn inserts_values_verbatim() {
+        assert_eq!(
+            render("Room: {{room}}", &variables()).expect("rendered"),
+            "Room: {{rent}} & <b>"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_variables() {
+        assert_eq!(
+            render("{{rent}} {{deposit}}", &variables())
+                .expect_err("unknown variable")
+                .to_string(),
+            "Unknown template variable: deposit"
+        );
+    }
+
+    #[test]
+    fn rejects_unclosed_placeholders() {
+        assert_eq!(
+            render("Rent: {{rent", &variables())
+                .expect_err("unclosed placeholder")
+                .to_string(),
+            "Unclosed placeholder in template"
+        );
+    }
+}
@@ -0,0 +1,17 @@
+use serde::Serialize;
+
+use crate::functions::bool_to_int_str;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize)]
+pub struct VerificationData<'code> {
+    #[serde(rename = "verification_code")]
+    code: &'code str,
+    #[serde(rename = "login_form_autologin", serialize_with = "bool_to_int_str")]
+    autologin: bool,
+}
+
+impl<'code> VerificationData<'code> {
+    pub const fn new(code: &'code str, autologin: bool) -> Self {
+        Self { code, autologin }
+    }
+}
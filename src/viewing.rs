@@ -0,0 +1,250 @@
+use std::fmt::{Display, Formatter, Write};
+use std::path::Path;
+
+use anyhow::anyhow;
+use chrono::{NaiveDateTime, TimeDelta, Utc};
+use serde::Deserialize;
+use serde_rw::FromFile;
+
+const DEFAULT_DURATION_MIN: u32 = 30;
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+const MAX_LINE_LENGTH: usize = 75;
+
+/// Scheduled viewings as defined in a local file.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+pub struct Viewings {
+    pub(crate) location: Option<String>,
+    #[serde(default)]
+    pub(crate) viewings: Vec<Viewing>,
+}
+
+/// A viewing of an offer at a given time.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+pub struct Viewing {
+    pub(crate) offer: u32,
+    pub(crate) start: NaiveDateTime,
+    pub(crate) duration_min: Option<u32>,
+    pub(crate) title: Option<String>,
+    pub(crate) location: Option<String>,
+    #[serde(default)]
+    pub(crate) applicants: Vec<String>,
+    pub(crate) conversation: Option<u32>,
+    pub(crate) notes: Option<String>,
+}
+
+impl Viewings {
+    /// Load the viewings from a JSON or TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`anyhow::Error`] if the file could not be read or parsed.
+    pub fn load(file: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::from_file(file).map_err(|error| anyhow!("{error}"))
+    }
+
+    /// Return the viewings as an iCalendar.
+    #[must_use]
+    pub const fn calendar(&self) -> Calendar<'_> {
+        Calendar(self)
+    }
+}
+
+impl Viewing {
+    fn end(&self) -> NaiveDateTime {
+        self.start
+            + TimeDelta::minutes(i64::from(self.duration_min.unwrap_or(DEFAULT_DURATION_MIN)))
+    }
+
+    /// Return a unique ID that stays the same across exports.
+    ///
+    /// Viewings of the same offer at the same time are told apart by their conversation
+    /// or, if none is given, by their applicants.
+    fn uid(&self) -> String {
+        let mut uid = format!("{}-{}", self.start.format(DATE_TIME_FORMAT), self.offer);
+
+        if let Some(conversation) = self.conversation {
+            let _ = write!(uid, "-{conversation}");
+        } else if !self.applicants.is_empty() {
+            let _ = write!(uid, "-{}", self.applicants.join("+"));
+        }
+
+        uid.push_str("@wg_gesucht_updater");
+        uid
+    }
+
+    fn summary(&self) -> String {
+        let mut summary = self
+            .title
+            .clone()
+            .unwrap_or_else(|| format!("Casting for offer {}", self.offer));
+
+        if !self.applicants.is_empty() {
+            let _ = write!(summary, " ({})", self.applicants.join(", "));
+        }
+
+        summary
+    }
+
+    fn description(&self) -> String {
+        let mut description = format!("https://www.wg-gesucht.de/{}.html", self.offer);
+
+        for applicant in &self.applicants {
+            let _ = write!(description, "\nApplicant: {applicant}");
+        }
+
+        if let Some(notes) = &self.notes {
+            let _ = write!(description, "\n\n{notes}");
+        }
+
+        description
+    }
+}
+
+/// iCalendar (RFC 5545) representation of viewings.
+///
+/// Times are written as floating local times, i.e. in the time zone of the importing calendar.
+#[derive(Clone, Copy, Debug)]
+pub struct Calendar<'viewings>(&'viewings Viewings);
+
+impl Display for Calendar<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        write_line(f, "BEGIN:VCALENDAR")?;
+        write_line(f, "VERSION:2.0")?;
+        write_line(f, "PRODID:-//wg_gesucht_updater//viewings//EN")?;
+
+        for viewing in &self.0.viewings {
+            let start = viewing.start.format(DATE_TIME_FORMAT);
+            write_line(f, "BEGIN:VEVENT")?;
+            write_line(f, &format!("UID:{}", escape(&viewing.uid())))?;
+            write_line(f, &format!("DTSTAMP:{stamp}"))?;
+            write_line(f, &format!("DTSTART:{start}"))?;
+            write_line(
+                f,
+                &format!("DTEND:{}", viewing.end().format(DATE_TIME_FORMAT)),
+            )?;
+            write_line(f, &format!("SUMMARY:{}", escape(&viewing.summary())))?;
+            write_line(
+                f,
+                &format!("DESCRIPTION:{}", escape(&viewing.description())),
+            )?;
+
+            if let Some(location) = viewing.location.as_ref().or(self.0.location.as_ref()) {
+                write_line(f, &format!("LOCATION:{}", escape(location)))?;
+            }
+
+            write_line(f, "END:VEVENT")?;
+        }
+
+        write_line(f, "END:VCALENDAR")
+    }
+}
+
+/// Escape a text value as required by RFC 5545.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Write a content line terminated by CRLF, folding it after 75 octets.
+fn write_line(f: &mut Formatter<'_>, line: &str) -> std::fmt::Result {
+    let mut length = 0;
+
+    for char in line.chars() {
+        if length + char.len_utf8() > MAX_LINE_LENGTH {
+            f.write_str("\r\n ")?;
+            length = 1;
+        }
+
+        f.write_char(char)?;
+        length += char.len_utf8();
+    }
+
+    f.write_str("\r\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::{Display, Formatter};
+
+    use chrono::NaiveDateTime;
+
+    use super::{Viewing, escape, write_line};
+
+    struct Line<'line>(&'line str);
+
+    impl Display for Line<'_> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write_line(f, self.0)
+        }
+    }
+
+    fn viewing(applicants: &[&str], conversation: Option<u32>) -> Viewing {
+        Viewing {
+            offer: 1234,
+            start: NaiveDateTime::parse_from_str("2026-11-05T19:00:00", "%Y-%m-%dT%H:%M:%S")
+                .expect("valid date"),
+            duration_min: None,
+            title: None,
+            location: None,
+            applicants: applicants.iter().map(ToString::to_string).collect(),
+            conversation,
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn escape_text() {
+        assert_eq!(escape("a\\b;c,d\ne"), "a\\\\b\\;c\\,d\\ne");
+    }
+
+    #[test]
+    fn short_lines_are_not_folded() {
+        assert_eq!(Line("SUMMARY:Casting").to_string(), "SUMMARY:Casting\r\n");
+    }
+
+    #[test]
+    fn long_lines_are_folded_after_75_octets() {
+        let line = "x".repeat(160);
+        assert_eq!(
+            Line(&line).to_string(),
+            format!(
+                "{}\r\n {}\r\n {}\r\n",
+                "x".repeat(75),
+                "x".repeat(74),
+                "x".repeat(11)
+            )
+        );
+    }
+
+    #[test]
+    fn folding_does_not_split_characters() {
+        let line = format!("{}ää", "x".repeat(74));
+        assert_eq!(
+            Line(&line).to_string(),
+            format!("{}\r\n ää\r\n", "x".repeat(74))
+        );
+    }
+
+    #[test]
+    fn uids_are_unique_per_conversation_or_applicants() {
+        assert_eq!(
+            viewing(&[], None).uid(),
+            "20261105T190000-1234@wg_gesucht_updater"
+        );
+        assert_eq!(
+            viewing(&["Jane Doe", "John Doe"], None).uid(),
+            "20261105T190000-1234-Jane Doe+John Doe@wg_gesucht_updater"
+        );
+        assert_eq!(
+            viewing(&["Jane Doe"], Some(42)).uid(),
+            "20261105T190000-1234-42@wg_gesucht_updater"
+        );
+        assert_ne!(
+            viewing(&["Jane Doe"], None).uid(),
+            viewing(&["John Doe"], None).uid()
+        );
+    }
+}